@@ -0,0 +1,10 @@
+-- Existing comments get the time of the migration, as it's unknown when they were written.
+-- Columns added by ALTER TABLE can't default to CURRENT_TIMESTAMP, which wouldn't be in the
+-- RFC 3339 format sqlx writes anyway, and dates are compared as text.
+ALTER TABLE comment ADD COLUMN created_at DATETIME NOT NULL DEFAULT 0;
+ALTER TABLE comment ADD COLUMN updated_at DATETIME NOT NULL DEFAULT 0;
+
+UPDATE comment SET created_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now'), updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now');
+
+CREATE INDEX comment_post_id_created_at ON comment (post_id, created_at);
+CREATE INDEX comment_blog_id_created_at ON comment (blog_id, created_at);
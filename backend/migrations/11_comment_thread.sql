@@ -0,0 +1,16 @@
+-- Replies. Top level comments have no parent and a depth of 0.
+ALTER TABLE comment ADD COLUMN parent_id INTEGER REFERENCES comment(id) ON DELETE CASCADE;
+ALTER TABLE comment ADD COLUMN depth INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX comment_parent_id ON comment (parent_id);
+
+CREATE TABLE comment_settings (
+    blog_id INTEGER NOT NULL REFERENCES blog(id) ON DELETE CASCADE,
+
+    -- Deepest reply level allowed. 0 disables replies.
+    max_depth INTEGER NOT NULL DEFAULT 3,
+
+    updated_at DATETIME NOT NULL,
+
+    PRIMARY KEY (blog_id)
+);
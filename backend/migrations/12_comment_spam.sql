@@ -0,0 +1,37 @@
+-- Spam check results. `spam_reasons` is a JSON list of the signals raised.
+ALTER TABLE comment ADD COLUMN spam_score REAL NOT NULL DEFAULT 0;
+ALTER TABLE comment ADD COLUMN spam_reasons TEXT;
+-- Which way the comment trained the classifier. NULL when untrained.
+ALTER TABLE comment ADD COLUMN trained_spam INTEGER;
+
+CREATE TABLE spam_blocklist (
+    blog_id INTEGER NOT NULL REFERENCES blog(id) ON DELETE CASCADE,
+
+    word TEXT COLLATE NOCASE NOT NULL,
+
+    created_at DATETIME NOT NULL,
+
+    PRIMARY KEY (blog_id, word)
+);
+
+-- Bayesian classifier: number of spam/ham comments each token was seen in
+CREATE TABLE spam_token (
+    blog_id INTEGER NOT NULL REFERENCES blog(id) ON DELETE CASCADE,
+
+    token TEXT NOT NULL,
+
+    spam_count INTEGER NOT NULL DEFAULT 0,
+    ham_count INTEGER NOT NULL DEFAULT 0,
+
+    PRIMARY KEY (blog_id, token)
+);
+
+-- Bayesian classifier: number of spam/ham comments trained on
+CREATE TABLE spam_corpus (
+    blog_id INTEGER NOT NULL REFERENCES blog(id) ON DELETE CASCADE,
+
+    spam_count INTEGER NOT NULL DEFAULT 0,
+    ham_count INTEGER NOT NULL DEFAULT 0,
+
+    PRIMARY KEY (blog_id)
+);
@@ -0,0 +1,9 @@
+ALTER TABLE comment_settings ADD COLUMN enabled INTEGER NOT NULL DEFAULT 1;
+-- Comments from members signed into the website skip the moderation queue
+ALTER TABLE comment_settings ADD COLUMN auto_approve_members INTEGER NOT NULL DEFAULT 0;
+-- Days after the post date when comments close. NULL keeps them open.
+ALTER TABLE comment_settings ADD COLUMN close_after_days INTEGER;
+ALTER TABLE comment_settings ADD COLUMN require_email INTEGER NOT NULL DEFAULT 0;
+
+-- Overrides the blog's `enabled` setting when set
+ALTER TABLE post ADD COLUMN comments_enabled INTEGER;
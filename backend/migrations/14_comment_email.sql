@@ -0,0 +1,50 @@
+-- Rebuilt to drop the UNIQUE on email, which kept readers from commenting more than once.
+--
+-- The new table references itself by its own name so dropping the old one doesn't cascade
+-- into it. Renaming updates the reference.
+CREATE TABLE comment_new (
+    id INTEGER NOT NULL,
+
+    blog_id INTEGER NOT NULL REFERENCES blog(id) ON DELETE CASCADE,
+    post_id INTEGER NOT NULL REFERENCES post(id) ON DELETE CASCADE,
+
+    -- Replies. Top level comments have no parent and a depth of 0.
+    parent_id INTEGER REFERENCES comment_new(id) ON DELETE CASCADE,
+    depth INTEGER NOT NULL DEFAULT 0,
+
+    external_member_id TEXT,
+
+    author_name TEXT NOT NULL,
+
+    email TEXT,
+    comment TEXT NOT NULL,
+
+    status INTEGER NOT NULL,
+
+    -- Spam check results. `spam_reasons` is a JSON list of the signals raised.
+    spam_score REAL NOT NULL DEFAULT 0,
+    spam_reasons TEXT,
+    -- Which way the comment trained the classifier. NULL when untrained.
+    trained_spam INTEGER,
+
+    delete_reason TEXT,
+
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL,
+    -- Last time the author name or text was changed
+    edited_at DATETIME,
+    deleted_at DATETIME,
+
+    PRIMARY KEY ("id" AUTOINCREMENT)
+);
+
+INSERT INTO comment_new (id, blog_id, post_id, parent_id, depth, external_member_id, author_name, email, comment, status, spam_score, spam_reasons, trained_spam, delete_reason, created_at, updated_at, deleted_at)
+    SELECT id, blog_id, post_id, parent_id, depth, external_member_id, author_name, email, comment, status, spam_score, spam_reasons, trained_spam, delete_reason, created_at, updated_at, deleted_at FROM comment;
+
+DROP TABLE comment;
+
+ALTER TABLE comment_new RENAME TO comment;
+
+CREATE INDEX comment_parent_id ON comment (parent_id);
+CREATE INDEX comment_post_id_created_at ON comment (post_id, created_at);
+CREATE INDEX comment_blog_id_created_at ON comment (blog_id, created_at);
@@ -0,0 +1,50 @@
+-- Authors belong to a single blog. The same member can be an author on several blogs.
+DROP TABLE author;
+
+CREATE TABLE author (
+    id INTEGER NOT NULL,
+
+    blog_id INTEGER NOT NULL REFERENCES blog(id) ON DELETE CASCADE,
+
+    external_member_id TEXT NOT NULL,
+
+    name TEXT NOT NULL,
+    slug TEXT COLLATE NOCASE NOT NULL,
+    email TEXT,
+
+    bio TEXT,
+    avatar_url TEXT,
+    -- JSON list of `{ "name", "url" }`
+    links TEXT NOT NULL DEFAULT '[]',
+
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL,
+
+    UNIQUE(blog_id, external_member_id),
+    UNIQUE(blog_id, slug),
+    PRIMARY KEY ("id" AUTOINCREMENT)
+);
+
+-- Blogs installed before authors existed start out with their owner, under a placeholder name
+-- they can change from the dashboard. Installing a blog uses the same one.
+INSERT INTO author (blog_id, external_member_id, name, slug, created_at, updated_at)
+    SELECT id, external_member_id, 'Owner', 'owner', strftime('%Y-%m-%dT%H:%M:%SZ', 'now'), strftime('%Y-%m-%dT%H:%M:%SZ', 'now') FROM blog;
+
+-- Main author, shown first
+ALTER TABLE post ADD COLUMN author_id INTEGER REFERENCES author(id) ON DELETE SET NULL;
+
+CREATE INDEX post_author_id ON post (author_id);
+
+-- Co-authors, in the order they're listed
+CREATE TABLE post_author (
+    blog_id INTEGER NOT NULL REFERENCES blog(id) ON DELETE CASCADE,
+
+    post_id INTEGER NOT NULL REFERENCES post(id) ON DELETE CASCADE,
+    author_id INTEGER NOT NULL REFERENCES author(id) ON DELETE CASCADE,
+
+    position INTEGER NOT NULL DEFAULT 0,
+
+    PRIMARY KEY (post_id, author_id)
+);
+
+CREATE INDEX post_author_author_id ON post_author (author_id);
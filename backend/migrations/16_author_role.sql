@@ -0,0 +1,5 @@
+-- 0 = Owner, 1 = Editor, 2 = Author, 3 = Contributor
+ALTER TABLE author ADD COLUMN role INTEGER NOT NULL DEFAULT 2;
+
+UPDATE author SET role = 0
+    WHERE external_member_id = (SELECT blog.external_member_id FROM blog WHERE blog.id = author.blog_id);
@@ -0,0 +1,13 @@
+-- Previous slugs of posts, kept so old links can be redirected
+CREATE TABLE post_slug_history (
+    blog_id INTEGER NOT NULL REFERENCES blog(id) ON DELETE CASCADE,
+
+    slug TEXT COLLATE NOCASE NOT NULL,
+    post_id INTEGER NOT NULL REFERENCES post(id) ON DELETE CASCADE,
+
+    created_at DATETIME NOT NULL,
+
+    PRIMARY KEY (blog_id, slug)
+);
+
+CREATE INDEX post_slug_history_post_id ON post_slug_history (post_id);
@@ -0,0 +1,18 @@
+CREATE TABLE post_revision (
+    id INTEGER NOT NULL,
+
+    blog_id INTEGER NOT NULL REFERENCES blog(id) ON DELETE CASCADE,
+    post_id INTEGER NOT NULL REFERENCES post(id) ON DELETE CASCADE,
+
+    title TEXT NOT NULL,
+    content TEXT NOT NULL,
+
+    restored_from INTEGER REFERENCES post_revision(id) ON DELETE SET NULL,
+
+    created_at DATETIME NOT NULL,
+
+    PRIMARY KEY ("id" AUTOINCREMENT)
+);
+
+INSERT INTO post_revision (blog_id, post_id, title, content, created_at)
+SELECT blog_id, id, title, content, updated_at FROM post;
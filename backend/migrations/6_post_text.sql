@@ -0,0 +1,8 @@
+-- Derived from content on every save. Filled in for existing posts on startup.
+ALTER TABLE post ADD COLUMN plain_text TEXT NOT NULL DEFAULT '';
+ALTER TABLE post ADD COLUMN excerpt TEXT NOT NULL DEFAULT '';
+ALTER TABLE post ADD COLUMN word_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE post ADD COLUMN read_minutes INTEGER NOT NULL DEFAULT 0;
+
+-- Author supplied, replaces the generated excerpt
+ALTER TABLE post ADD COLUMN custom_excerpt TEXT;
@@ -0,0 +1,59 @@
+-- rowid is the post id
+CREATE VIRTUAL TABLE post_search USING fts5(
+    title,
+    body,
+    tags,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER post_search_insert AFTER INSERT ON post BEGIN
+    INSERT INTO post_search (rowid, title, body, tags)
+    VALUES (
+        new.id,
+        new.title,
+        new.plain_text,
+        (SELECT group_concat(tag.name, ' ') FROM post_tag INNER JOIN tag ON tag.id = post_tag.tag_id WHERE post_tag.post_id = new.id)
+    );
+END;
+
+CREATE TRIGGER post_search_update AFTER UPDATE OF title, plain_text ON post BEGIN
+    DELETE FROM post_search WHERE rowid = old.id;
+
+    INSERT INTO post_search (rowid, title, body, tags)
+    VALUES (
+        new.id,
+        new.title,
+        new.plain_text,
+        (SELECT group_concat(tag.name, ' ') FROM post_tag INNER JOIN tag ON tag.id = post_tag.tag_id WHERE post_tag.post_id = new.id)
+    );
+END;
+
+CREATE TRIGGER post_search_delete AFTER DELETE ON post BEGIN
+    DELETE FROM post_search WHERE rowid = old.id;
+END;
+
+CREATE TRIGGER post_search_tag_insert AFTER INSERT ON post_tag BEGIN
+    UPDATE post_search
+    SET tags = (SELECT group_concat(tag.name, ' ') FROM post_tag INNER JOIN tag ON tag.id = post_tag.tag_id WHERE post_tag.post_id = new.post_id)
+    WHERE rowid = new.post_id;
+END;
+
+CREATE TRIGGER post_search_tag_delete AFTER DELETE ON post_tag BEGIN
+    UPDATE post_search
+    SET tags = (SELECT group_concat(tag.name, ' ') FROM post_tag INNER JOIN tag ON tag.id = post_tag.tag_id WHERE post_tag.post_id = old.post_id)
+    WHERE rowid = old.post_id;
+END;
+
+CREATE TRIGGER post_search_tag_rename AFTER UPDATE OF name ON tag BEGIN
+    UPDATE post_search
+    SET tags = (SELECT group_concat(tag.name, ' ') FROM post_tag INNER JOIN tag ON tag.id = post_tag.tag_id WHERE post_tag.post_id = post_search.rowid)
+    WHERE rowid IN (SELECT post_id FROM post_tag WHERE tag_id = new.id);
+END;
+
+INSERT INTO post_search (rowid, title, body, tags)
+SELECT
+    post.id,
+    post.title,
+    post.plain_text,
+    (SELECT group_concat(tag.name, ' ') FROM post_tag INNER JOIN tag ON tag.id = post_tag.tag_id WHERE post_tag.post_id = post.id)
+FROM post;
@@ -0,0 +1,32 @@
+-- Categories could never be created through the API, so there is nothing to carry over.
+DROP TABLE post_category;
+DROP TABLE category;
+
+CREATE TABLE category (
+    id INTEGER NOT NULL,
+
+    blog_id INTEGER NOT NULL REFERENCES blog(id) ON DELETE CASCADE,
+    parent_id INTEGER REFERENCES category(id) ON DELETE SET NULL,
+
+    name TEXT NOT NULL,
+    slug TEXT COLLATE NOCASE NOT NULL,
+    description TEXT,
+
+    display_order INTEGER NOT NULL DEFAULT 0,
+
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL,
+
+    UNIQUE(blog_id, slug),
+    PRIMARY KEY ("id" AUTOINCREMENT)
+);
+
+CREATE TABLE post_category
+(
+    blog_id INTEGER NOT NULL REFERENCES blog(id) ON DELETE CASCADE,
+
+    post_id INTEGER NOT NULL REFERENCES post(id) ON DELETE CASCADE,
+    category_id INTEGER NOT NULL REFERENCES category(id) ON DELETE CASCADE,
+
+    PRIMARY KEY (blog_id, post_id, category_id)
+);
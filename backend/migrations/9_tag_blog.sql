@@ -0,0 +1,60 @@
+-- Tags could never be created through the API, so there is nothing to carry over.
+DROP TABLE post_tag;
+DROP TABLE tag;
+
+CREATE TABLE tag (
+    id INTEGER NOT NULL,
+
+    blog_id INTEGER NOT NULL REFERENCES blog(id) ON DELETE CASCADE,
+
+    name TEXT NOT NULL,
+    slug TEXT COLLATE NOCASE NOT NULL,
+
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL,
+
+    UNIQUE(blog_id, slug),
+    PRIMARY KEY ("id" AUTOINCREMENT)
+);
+
+CREATE TABLE post_tag
+(
+    blog_id INTEGER NOT NULL REFERENCES blog(id) ON DELETE CASCADE,
+
+    post_id INTEGER NOT NULL REFERENCES post(id) ON DELETE CASCADE,
+    tag_id INTEGER NOT NULL REFERENCES tag(id) ON DELETE CASCADE,
+
+    PRIMARY KEY (blog_id, post_id, tag_id)
+);
+
+-- Previous slugs of renamed or merged tags, so old URLs keep working.
+CREATE TABLE tag_slug_alias
+(
+    blog_id INTEGER NOT NULL REFERENCES blog(id) ON DELETE CASCADE,
+
+    slug TEXT COLLATE NOCASE NOT NULL,
+    tag_id INTEGER NOT NULL REFERENCES tag(id) ON DELETE CASCADE,
+
+    PRIMARY KEY (blog_id, slug)
+);
+
+-- Dropped along with the tables above
+CREATE TRIGGER post_search_tag_insert AFTER INSERT ON post_tag BEGIN
+    UPDATE post_search
+    SET tags = (SELECT group_concat(tag.name, ' ') FROM post_tag INNER JOIN tag ON tag.id = post_tag.tag_id WHERE post_tag.post_id = new.post_id)
+    WHERE rowid = new.post_id;
+END;
+
+CREATE TRIGGER post_search_tag_delete AFTER DELETE ON post_tag BEGIN
+    UPDATE post_search
+    SET tags = (SELECT group_concat(tag.name, ' ') FROM post_tag INNER JOIN tag ON tag.id = post_tag.tag_id WHERE post_tag.post_id = old.post_id)
+    WHERE rowid = old.post_id;
+END;
+
+CREATE TRIGGER post_search_tag_rename AFTER UPDATE OF name ON tag BEGIN
+    UPDATE post_search
+    SET tags = (SELECT group_concat(tag.name, ' ') FROM post_tag INNER JOIN tag ON tag.id = post_tag.tag_id WHERE post_tag.post_id = post_search.rowid)
+    WHERE rowid IN (SELECT post_id FROM post_tag WHERE tag_id = new.id);
+END;
+
+UPDATE post_search SET tags = NULL;
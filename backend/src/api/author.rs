@@ -0,0 +1,403 @@
+use webby_addon_common::{
+    AddonInstanceUuid, JsonListResponse, JsonResponse, ListResponse, MemberUuid, WrappingResponse,
+};
+use axum::{
+    extract,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use sqlx::{SqliteConnection, SqlitePool};
+use time::OffsetDateTime;
+
+use super::member::Member;
+use crate::{
+    models::{
+        slugify, AuthorLink, AuthorModel, AuthorRole, BlogModel, NewAuthorModel, PostModel,
+        PostQuery, PostSort, PostStatus, SortOrder,
+    },
+    AuthorId, BlogId, Error, Result,
+};
+
+const MAX_AUTHOR_LINKS: usize = 10;
+
+const DEFAULT_POST_LIMIT: i64 = 10;
+const MAX_POST_LIMIT: i64 = 50;
+
+pub fn routes() -> Router<SqlitePool> {
+    Router::new()
+        .route("/:instance/authors", get(get_author_list))
+        .route("/:instance/authors/:slug", get(get_author_profile))
+        .route("/:instance/author", post(create_author))
+        .route(
+            "/:instance/author/:author_id",
+            get(get_author).post(update_author).delete(delete_author),
+        )
+}
+
+/// Author fields which are safe to show to readers.
+pub(super) fn public_author(author: &AuthorModel) -> serde_json::Value {
+    serde_json::json!({
+        "id": author.id,
+        "name": author.name,
+        "slug": author.slug,
+        "bio": author.bio,
+        "avatar_url": author.avatar_url,
+        "links": author.links,
+    })
+}
+
+async fn get_author_list(
+    extract::Path(instance_id): extract::Path<AddonInstanceUuid>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+) -> Result<JsonListResponse<AuthorModel>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    member.author(blog.id, &mut acq).await?;
+
+    let authors = AuthorModel::find_by_blog_id(blog.id, &mut acq).await?;
+
+    Ok(Json(WrappingResponse::okay(ListResponse::all(authors))))
+}
+
+#[derive(Deserialize)]
+struct AuthorPostsQuery {
+    offset: Option<i64>,
+    limit: Option<i64>,
+}
+
+/// Public profile along with the published posts they wrote or co-authored.
+async fn get_author_profile(
+    extract::Path((instance_id, slug)): extract::Path<(AddonInstanceUuid, String)>,
+    extract::State(db): extract::State<SqlitePool>,
+    extract::Query(AuthorPostsQuery { offset, limit }): extract::Query<AuthorPostsQuery>,
+) -> Result<JsonResponse<serde_json::Value>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    let Some(author) = AuthorModel::find_one_by_slug(blog.id, &slug, &mut acq).await? else {
+        return Err(Error::NotFound("Author not found"));
+    };
+
+    let query = PostQuery {
+        status: Some(PostStatus::Published),
+        category: None,
+        tag: None,
+        author: Some(author.slug.clone()),
+        from: None,
+        to: None,
+        visible_at: Some(OffsetDateTime::now_utc()),
+        sort: vec![(PostSort::PostDate, SortOrder::Desc)],
+        offset: offset.unwrap_or(0).max(0),
+        limit: limit.unwrap_or(DEFAULT_POST_LIMIT).clamp(1, MAX_POST_LIMIT),
+    };
+
+    let (posts, total) = PostModel::find_by_query(blog.id, &query, &mut acq).await?;
+
+    let items = posts
+        .iter()
+        .map(|post| {
+            serde_json::json!({
+                "id": post.id,
+                "slug": post.slug,
+                "title": post.title,
+                "excerpt": post.display_excerpt(),
+                "read_minutes": post.read_minutes,
+                "post_date": post.post_date,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Json(WrappingResponse::okay(serde_json::json!({
+        "author": public_author(&author),
+        "posts": ListResponse {
+            offset: query.offset,
+            limit: query.limit,
+            total,
+            items,
+        },
+    }))))
+}
+
+#[derive(Deserialize)]
+struct CreateAuthorJson {
+    external_member_id: MemberUuid,
+    name: String,
+    slug: Option<String>,
+    email: Option<String>,
+    bio: Option<String>,
+    avatar_url: Option<String>,
+    #[serde(default)]
+    links: Vec<AuthorLink>,
+    role: Option<AuthorRole>,
+}
+
+async fn create_author(
+    extract::Path(instance_id): extract::Path<AddonInstanceUuid>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+    extract::Json(CreateAuthorJson {
+        external_member_id,
+        name,
+        slug,
+        email,
+        bio,
+        avatar_url,
+        links,
+        role,
+    }): extract::Json<CreateAuthorJson>,
+) -> Result<JsonResponse<AuthorModel>> {
+    let mut tx = db.begin().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut tx).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    member
+        .author_with(blog.id, AuthorRole::can_manage_authors, &mut tx)
+        .await?;
+
+    if AuthorModel::find_one_by_member_id(blog.id, external_member_id, &mut tx)
+        .await?
+        .is_some()
+    {
+        return Err(eyre::eyre!("Member already has an author profile"))?;
+    }
+
+    let name = name.trim().to_string();
+
+    if name.is_empty() {
+        return Err(eyre::eyre!("Author name is required"))?;
+    }
+
+    let slug = author_slug(blog.id, slug.as_deref().unwrap_or(&name), None, &mut tx).await?;
+
+    let role = role.unwrap_or(AuthorRole::Author);
+
+    if role == AuthorRole::Owner {
+        return Err(Error::Forbidden("A blog only has one owner"));
+    }
+
+    let avatar_url = non_empty(avatar_url);
+    check_links(avatar_url.as_deref(), &links)?;
+
+    let author = NewAuthorModel {
+        blog_id: blog.id,
+        external_member_id,
+        name,
+        slug,
+        email: non_empty(email),
+        bio: non_empty(bio),
+        avatar_url,
+        links,
+        role,
+    }
+    .insert(&mut tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(WrappingResponse::okay(author)))
+}
+
+async fn get_author(
+    extract::Path((instance_id, author_id)): extract::Path<(AddonInstanceUuid, AuthorId)>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+) -> Result<JsonResponse<AuthorModel>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    member.author(blog.id, &mut acq).await?;
+
+    let Some(author) = AuthorModel::find_one_by_id(blog.id, author_id, &mut acq).await? else {
+        return Err(Error::NotFound("Author not found"));
+    };
+
+    Ok(Json(WrappingResponse::okay(author)))
+}
+
+#[derive(Deserialize)]
+struct UpdateAuthorJson {
+    name: Option<String>,
+    slug: Option<String>,
+    /// Empty values clear the email, bio and avatar
+    email: Option<String>,
+    bio: Option<String>,
+    avatar_url: Option<String>,
+    /// Replaces the current links when set
+    links: Option<Vec<AuthorLink>>,
+    /// Only the owner can change roles
+    role: Option<AuthorRole>,
+}
+
+async fn update_author(
+    extract::Path((instance_id, author_id)): extract::Path<(AddonInstanceUuid, AuthorId)>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+    extract::Json(UpdateAuthorJson {
+        name,
+        slug,
+        email,
+        bio,
+        avatar_url,
+        links,
+        role,
+    }): extract::Json<UpdateAuthorJson>,
+) -> Result<JsonResponse<AuthorModel>> {
+    let mut tx = db.begin().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut tx).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    let current = member.author(blog.id, &mut tx).await?;
+
+    let Some(mut author) = AuthorModel::find_one_by_id(blog.id, author_id, &mut tx).await? else {
+        return Err(Error::NotFound("Author not found"));
+    };
+
+    // Everyone can edit their own profile
+    if current.id != author.id && !current.role.can_manage_authors() {
+        return Err(Error::Forbidden("You can only edit your own profile"));
+    }
+
+    if let Some(role) = role.filter(|v| *v != author.role) {
+        if !current.role.can_manage_authors() {
+            return Err(Error::Forbidden("Only the owner can change roles"));
+        }
+
+        if role == AuthorRole::Owner || author.role == AuthorRole::Owner {
+            return Err(Error::Forbidden("A blog only has one owner"));
+        }
+
+        author.role = role;
+    }
+
+    if let Some(name) = name {
+        let name = name.trim().to_string();
+
+        if name.is_empty() {
+            return Err(eyre::eyre!("Author name is required"))?;
+        }
+
+        author.name = name;
+    }
+
+    if let Some(slug) = slug {
+        author.slug = author_slug(blog.id, &slug, Some(author.id), &mut tx).await?;
+    }
+
+    if let Some(email) = email {
+        author.email = non_empty(Some(email));
+    }
+
+    if let Some(bio) = bio {
+        author.bio = non_empty(Some(bio));
+    }
+
+    if let Some(avatar_url) = avatar_url {
+        author.avatar_url = non_empty(Some(avatar_url));
+    }
+
+    if let Some(links) = links {
+        author.links.0 = links;
+    }
+
+    check_links(author.avatar_url.as_deref(), &author.links)?;
+
+    author.update(&mut tx).await?;
+
+    tx.commit().await?;
+
+    Ok(Json(WrappingResponse::okay(author)))
+}
+
+async fn delete_author(
+    extract::Path((instance_id, author_id)): extract::Path<(AddonInstanceUuid, AuthorId)>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+) -> Result<JsonResponse<&'static str>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    member
+        .author_with(blog.id, AuthorRole::can_manage_authors, &mut acq)
+        .await?;
+
+    let Some(author) = AuthorModel::find_one_by_id(blog.id, author_id, &mut acq).await? else {
+        return Err(Error::NotFound("Author not found"));
+    };
+
+    if author.external_member_id == blog.external_member_id {
+        return Err(eyre::eyre!("The blog owner can't be removed"))?;
+    }
+
+    AuthorModel::delete(blog.id, author.id, &mut acq).await?;
+
+    Ok(Json(WrappingResponse::okay("success")))
+}
+
+async fn author_slug(
+    blog_id: BlogId,
+    value: &str,
+    current: Option<AuthorId>,
+    db: &mut SqliteConnection,
+) -> Result<String> {
+    let slug = slugify(value);
+
+    if slug.is_empty() {
+        return Err(eyre::eyre!("Author slug is empty"))?;
+    }
+
+    if let Some(found) = AuthorModel::find_one_by_slug(blog_id, &slug, db).await? {
+        if Some(found.id) != current {
+            return Err(eyre::eyre!("Author slug is already in use"))?;
+        }
+    }
+
+    Ok(slug)
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Only web links are allowed so they can't run scripts on the profile page.
+fn check_links(avatar_url: Option<&str>, links: &[AuthorLink]) -> Result<()> {
+    if links.len() > MAX_AUTHOR_LINKS {
+        return Err(eyre::eyre!("At most {MAX_AUTHOR_LINKS} links are allowed").into());
+    }
+
+    let urls = avatar_url
+        .into_iter()
+        .chain(links.iter().map(|v| v.url.as_str()));
+
+    for url in urls {
+        if !(url.starts_with("https://") || url.starts_with("http://")) {
+            return Err(eyre::eyre!("Links must start with http:// or https://").into());
+        }
+    }
+
+    if links.iter().any(|v| v.name.trim().is_empty()) {
+        return Err(eyre::eyre!("Links need a name").into());
+    }
+
+    Ok(())
+}
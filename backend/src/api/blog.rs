@@ -7,35 +7,45 @@ use axum::{
     Json, Router,
 };
 use serde::Deserialize;
-use sqlx::SqlitePool;
+use sqlx::{SqliteConnection, SqlitePool};
+use time::{OffsetDateTime, UtcOffset};
 
+use super::member::Member;
 use crate::{
-    models::{BlogModel, NewPostModel, PostModel, PostStatus},
-    PostId, Result,
+    delta::{self, Delta},
+    models::{
+        is_valid_slug, slugify, truncate_slug, AuthorModel, BlogModel, CategoryModel, NewPostModel,
+        NewPostRevisionModel, NewTagModel, PostAuthorModel, PostCategoryModel, PostModel,
+        PostSlugHistoryModel, PostStatus, PostTagModel, TagModel, MAX_SLUG_LENGTH,
+    },
+    AuthorId, BlogId, CategoryId, Error, PostId, Result, TagId,
 };
 
 pub fn routes() -> Router<SqlitePool> {
     Router::new()
         .route("/:instance/overview", get(get_overview))
         .route("/:instance/analytics", get(get_analytics))
-        // .route("/:instance/categories", get(get_category_list))
-        // .route("/:instance/comments", get(get_comment_list))
-        // .route("/:instance/tags", get(get_tag_list))
         .route("/:instance/posts", get(get_post_list))
         .route("/:instance/post", post(create_post))
-        .route("/:instance/post/:post_id", get(get_post).post(update_post))
+        .route(
+            "/:instance/post/:post_id",
+            get(get_post).post(update_post).delete(delete_post),
+        )
 }
 
 async fn get_overview(
     extract::Path(instance_id): extract::Path<AddonInstanceUuid>,
     extract::State(db): extract::State<SqlitePool>,
+    member: Member,
 ) -> Result<JsonResponse<serde_json::Value>> {
     let mut acq = db.acquire().await?;
 
-    let Some(_blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
-        return Err(eyre::eyre!("Addon not found"))?;
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
     };
 
+    member.author(blog.id, &mut acq).await?;
+
     Ok(Json(WrappingResponse::okay(serde_json::json!({
         //
     }))))
@@ -44,13 +54,16 @@ async fn get_overview(
 async fn get_post_list(
     extract::Path(instance_id): extract::Path<AddonInstanceUuid>,
     extract::State(db): extract::State<SqlitePool>,
+    member: Member,
 ) -> Result<JsonListResponse<PostModel>> {
     let mut acq = db.acquire().await?;
 
     let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
-        return Err(eyre::eyre!("Addon not found"))?;
+        return Err(Error::NotFound("Addon not found"));
     };
 
+    member.author(blog.id, &mut acq).await?;
+
     let posts = PostModel::find_by_blog_id(blog.id, &mut acq).await?;
 
     Ok(Json(WrappingResponse::okay(ListResponse::all(posts))))
@@ -59,13 +72,16 @@ async fn get_post_list(
 async fn get_analytics(
     extract::Path(instance_id): extract::Path<AddonInstanceUuid>,
     extract::State(db): extract::State<SqlitePool>,
+    member: Member,
 ) -> Result<JsonResponse<serde_json::Value>> {
     let mut acq = db.acquire().await?;
 
-    let Some(_blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
-        return Err(eyre::eyre!("Addon not found"))?;
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
     };
 
+    member.author(blog.id, &mut acq).await?;
+
     Ok(Json(WrappingResponse::okay(serde_json::json!({
         //
     }))))
@@ -74,57 +90,178 @@ async fn get_analytics(
 #[derive(Deserialize)]
 struct CreatePostJson {
     title: String,
+    /// Generated from the title when missing
+    slug: Option<String>,
     content: serde_json::Value,
+    status: Option<PostStatus>,
+    excerpt: Option<String>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    post_date: Option<OffsetDateTime>,
+    /// Overrides the blog's comment setting when set
+    comments_enabled: Option<bool>,
+    /// Defaults to the signed in member. Only editors can pick someone else.
+    author_id: Option<AuthorId>,
+    #[serde(default)]
+    co_authors: Vec<AuthorId>,
+    #[serde(default)]
+    categories: Vec<TermRef<CategoryId>>,
+    #[serde(default)]
+    tags: Vec<TermRef<TagId>>,
 }
 
 async fn create_post(
     extract::Path(instance_id): extract::Path<AddonInstanceUuid>,
     extract::State(db): extract::State<SqlitePool>,
-    extract::Json(CreatePostJson { title, content }): extract::Json<CreatePostJson>,
+    member: Member,
+    extract::Json(CreatePostJson {
+        title,
+        slug,
+        content,
+        status,
+        excerpt,
+        post_date,
+        comments_enabled,
+        author_id,
+        co_authors,
+        categories,
+        tags,
+    }): extract::Json<CreatePostJson>,
 ) -> Result<JsonResponse<serde_json::Value>> {
-    let mut acq = db.acquire().await?;
+    let mut tx = db.begin().await?;
 
-    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
-        return Err(eyre::eyre!("Addon not found"))?;
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut tx).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    let current = member.author(blog.id, &mut tx).await?;
+
+    let post_date = post_date.map(|v| v.to_offset(UtcOffset::UTC));
+    let status = status.unwrap_or(PostStatus::Draft);
+
+    if !matches!(status, PostStatus::Draft) && !current.role.can_publish() {
+        return Err(Error::Forbidden("Contributors can only save drafts"));
+    }
+
+    let author = match author_id {
+        Some(id) if id != current.id => {
+            if !current.role.can_edit_others() {
+                return Err(Error::Forbidden("Only editors can write posts for others"));
+            }
+
+            find_author(blog.id, id, &mut tx).await?
+        }
+        _ => current,
+    };
+
+    let slug = match slug {
+        Some(slug) => Some(check_post_slug(blog.id, slug, None, &mut tx).await?),
+        None => unique_post_slug(blog.id, &slugify(&title), &mut tx).await?,
     };
 
     let post = NewPostModel {
         blog_id: blog.id,
-        slug: None,
+        author_id: Some(author.id),
+        slug,
         title,
         content,
-        status: PostStatus::Draft,
-        post_date: None,
+        custom_excerpt: excerpt.filter(|v| !v.trim().is_empty()),
+        status: post_date.map_or(status, |date| status.for_post_date(date)),
+        post_date,
+        comments_enabled,
+    }
+    .insert(&mut tx)
+    .await?;
+
+    if let Some(slug) = post.slug.as_deref() {
+        PostSlugHistoryModel::delete_by_slug(post.blog_id, slug, &mut tx).await?;
+    }
+
+    NewPostRevisionModel {
+        blog_id: post.blog_id,
+        post_id: post.id,
+        title: post.title.clone(),
+        content: post.content.0.clone(),
+        restored_from: None,
     }
-    .insert(&mut acq)
+    .insert(&mut tx)
     .await?;
 
+    set_post_co_authors(&post, co_authors, &mut tx).await?;
+    set_post_categories(&post, categories, &mut tx).await?;
+    set_post_tags(&post, tags, &mut tx).await?;
+
+    tx.commit().await?;
+
     Ok(Json(WrappingResponse::okay(serde_json::json!({
         "id": post.id,
         "slug": post.slug,
     }))))
 }
 
+#[derive(Deserialize, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum ContentFormat {
+    /// Raw Quill Delta
+    #[default]
+    Delta,
+    Html,
+}
+
+#[derive(Deserialize)]
+struct GetPostQuery {
+    #[serde(default)]
+    format: ContentFormat,
+}
+
 async fn get_post(
     extract::Path((instance_id, post_id)): extract::Path<(AddonInstanceUuid, i64)>,
     extract::State(db): extract::State<SqlitePool>,
+    extract::Query(GetPostQuery { format }): extract::Query<GetPostQuery>,
+    member: Member,
 ) -> Result<JsonResponse<serde_json::Value>> {
     let mut acq = db.acquire().await?;
 
     let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
-        return Err(eyre::eyre!("Addon not found"))?;
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    member.author(blog.id, &mut acq).await?;
+
+    let Some(post) = PostModel::find_one_by_id(blog.id, PostId::from(post_id), &mut acq).await?
+    else {
+        return Err(Error::NotFound("Post not found"));
     };
 
-    let Some(post) = PostModel::find_one_by_id(PostId::from(post_id), &mut acq).await? else {
-        return Err(eyre::eyre!("Post not found"))?;
+    let html = (format == ContentFormat::Html)
+        .then(|| delta::html::render(&Delta::from_value(&post.content)));
+
+    let author = match post.author_id {
+        Some(id) => AuthorModel::find_one_by_id(post.blog_id, id, &mut acq).await?,
+        None => None,
     };
 
+    let co_authors = AuthorModel::find_by_post_id(post.blog_id, post.id, &mut acq).await?;
+    let categories = CategoryModel::find_by_post_id(post.blog_id, post.id, &mut acq).await?;
+    let tags = TagModel::find_by_post_id(post.blog_id, post.id, &mut acq).await?;
+    let previous_slugs = PostSlugHistoryModel::find_by_post_id(post.id, &mut acq).await?;
+
     Ok(Json(WrappingResponse::okay(serde_json::json!({
         "id": post.id,
         "slug": post.slug,
+        "previous_slugs": previous_slugs.iter().map(|v| &v.slug).collect::<Vec<_>>(),
         "title": post.title,
         "content": post.content,
+        "html": html,
+        "excerpt": post.display_excerpt(),
+        "custom_excerpt": post.custom_excerpt,
+        "word_count": post.word_count,
+        "read_minutes": post.read_minutes,
         "status": post.status,
+        "comments_enabled": post.comments_enabled,
+        "author": author,
+        "co_authors": co_authors,
+        "categories": categories,
+        "tags": tags,
     }))))
 }
 
@@ -134,28 +271,67 @@ struct UpdatePostJson {
     content: Option<serde_json::Value>,
     status: Option<PostStatus>,
     slug: Option<String>,
+    /// An empty excerpt goes back to the generated one
+    excerpt: Option<String>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    post_date: Option<OffsetDateTime>,
+    /// `Some(None)` goes back to following the blog's comment setting
+    #[serde(default, deserialize_with = "super::deserialize_some")]
+    comments_enabled: Option<Option<bool>>,
+    /// `Some(None)` removes the main author
+    #[serde(default, deserialize_with = "super::deserialize_some")]
+    author_id: Option<Option<AuthorId>>,
+    /// Replaces the current co-authors when set
+    co_authors: Option<Vec<AuthorId>>,
+    /// Replaces the current categories when set
+    categories: Option<Vec<TermRef<CategoryId>>>,
+    /// Replaces the current tags when set
+    tags: Option<Vec<TermRef<TagId>>>,
 }
 
 async fn update_post(
     extract::Path((instance_id, post_id)): extract::Path<(AddonInstanceUuid, i64)>,
     extract::State(db): extract::State<SqlitePool>,
+    member: Member,
     extract::Json(UpdatePostJson {
         title,
         content,
         status,
         slug,
+        excerpt,
+        post_date,
+        comments_enabled,
+        author_id,
+        co_authors,
+        categories,
+        tags,
     }): extract::Json<UpdatePostJson>,
 ) -> Result<JsonResponse<serde_json::Value>> {
-    let mut acq = db.acquire().await?;
+    let mut tx = db.begin().await?;
 
-    let Some(_blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
-        return Err(eyre::eyre!("Addon not found"))?;
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut tx).await? else {
+        return Err(Error::NotFound("Addon not found"));
     };
 
-    let Some(mut post) = PostModel::find_one_by_id(PostId::from(post_id), &mut acq).await? else {
-        return Err(eyre::eyre!("Post not found"))?;
+    let current = member.author(blog.id, &mut tx).await?;
+
+    let Some(mut post) = PostModel::find_one_by_id(blog.id, PostId::from(post_id), &mut tx).await?
+    else {
+        return Err(Error::NotFound("Post not found"));
     };
 
+    check_can_edit_post(&current, &post, &mut tx).await?;
+
+    if !current.role.can_edit_others() && author_id.is_some_and(|v| v != post.author_id) {
+        return Err(Error::Forbidden("Only editors can change the author"));
+    }
+
+    if !current.role.can_publish() && status.is_some_and(|v| !matches!(v, PostStatus::Draft)) {
+        return Err(Error::Forbidden("Contributors can only save drafts"));
+    }
+
+    let content_changed = title.is_some() || content.is_some();
+
     if let Some(title) = title {
         post.title = title;
     }
@@ -164,18 +340,399 @@ async fn update_post(
         post.content.0 = content;
     }
 
+    if let Some(excerpt) = excerpt {
+        post.custom_excerpt = Some(excerpt).filter(|v| !v.trim().is_empty());
+    }
+
+    if let Some(post_date) = post_date {
+        post.post_date = post_date.to_offset(UtcOffset::UTC);
+    }
+
     if let Some(status) = status {
         post.status = status as u8 as i32;
     }
 
+    // Changing either the date or the status can move a post in or out of the schedule
+    if let Ok(status) = PostStatus::try_from(post.status as u8) {
+        post.status = status.for_post_date(post.post_date) as u8 as i32;
+    }
+
     if let Some(slug) = slug {
+        let slug = check_post_slug(post.blog_id, slug, Some(post.id), &mut tx).await?;
+
+        // Keep the old slug so links to it can be redirected
+        if let Some(old) = post.slug.as_deref().filter(|v| *v != slug) {
+            PostSlugHistoryModel::record(post.blog_id, post.id, old, &mut tx).await?;
+        }
+
+        PostSlugHistoryModel::delete_by_slug(post.blog_id, &slug, &mut tx).await?;
+
         post.slug = Some(slug);
     }
 
-    post.update(&mut acq).await?;
+    if let Some(comments_enabled) = comments_enabled {
+        post.comments_enabled = comments_enabled;
+    }
+
+    if let Some(author_id) = author_id {
+        post.author_id = match author_id {
+            Some(id) => Some(find_author(post.blog_id, id, &mut tx).await?.id),
+            None => None,
+        };
+    }
+
+    post.update(&mut tx).await?;
+
+    if content_changed {
+        NewPostRevisionModel {
+            blog_id: post.blog_id,
+            post_id: post.id,
+            title: post.title.clone(),
+            content: post.content.0.clone(),
+            restored_from: None,
+        }
+        .insert(&mut tx)
+        .await?;
+    }
+
+    // A new main author is dropped from the co-authors
+    let co_authors = match co_authors {
+        Some(co_authors) => Some(co_authors),
+        None if author_id.is_some() => Some(
+            AuthorModel::find_by_post_id(post.blog_id, post.id, &mut tx)
+                .await?
+                .into_iter()
+                .map(|v| v.id)
+                .collect(),
+        ),
+        None => None,
+    };
+
+    if let Some(co_authors) = co_authors {
+        set_post_co_authors(&post, co_authors, &mut tx).await?;
+    }
+
+    if let Some(categories) = categories {
+        set_post_categories(&post, categories, &mut tx).await?;
+    }
+
+    if let Some(tags) = tags {
+        set_post_tags(&post, tags, &mut tx).await?;
+    }
+
+    tx.commit().await?;
 
     Ok(Json(WrappingResponse::okay(serde_json::json!({
         "id": post.id,
         "slug": post.slug,
     }))))
 }
+
+#[derive(Deserialize)]
+struct DeletePostQuery {
+    reason: Option<String>,
+}
+
+/// Moves the post to the trash. It's purged automatically once the retention period passes.
+async fn delete_post(
+    extract::Path((instance_id, post_id)): extract::Path<(AddonInstanceUuid, i64)>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+    extract::Query(DeletePostQuery { reason }): extract::Query<DeletePostQuery>,
+) -> Result<JsonResponse<&'static str>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    let current = member.author(blog.id, &mut acq).await?;
+
+    let Some(post) = PostModel::find_one_by_id(blog.id, PostId::from(post_id), &mut acq).await?
+    else {
+        return Err(Error::NotFound("Post not found"));
+    };
+
+    check_can_edit_post(&current, &post, &mut acq).await?;
+
+    PostModel::delete(
+        blog.id,
+        post.id,
+        reason.filter(|v| !v.trim().is_empty()),
+        &mut acq,
+    )
+    .await?;
+
+    Ok(Json(WrappingResponse::okay("success")))
+}
+
+/// Editors can change any post, everyone else only the ones they (co-)wrote.
+/// Contributors are limited to drafts.
+pub(super) async fn check_can_edit_post(
+    current: &AuthorModel,
+    post: &PostModel,
+    db: &mut SqliteConnection,
+) -> Result<()> {
+    if !current.role.can_edit_others() {
+        let is_co_author = AuthorModel::find_by_post_id(post.blog_id, post.id, db)
+            .await?
+            .iter()
+            .any(|v| v.id == current.id);
+
+        if post.author_id != Some(current.id) && !is_co_author {
+            return Err(Error::Forbidden("You can only edit your own posts"));
+        }
+    }
+
+    if !current.role.can_publish() && post.status != PostStatus::Draft as u8 as i32 {
+        return Err(Error::Forbidden("Contributors can only edit drafts"));
+    }
+
+    Ok(())
+}
+
+/// Validates a slug picked by the user. It has to be unused, unlike generated ones.
+///
+/// Previous slugs of other posts can be picked, which stops them redirecting.
+async fn check_post_slug(
+    blog_id: BlogId,
+    slug: String,
+    current: Option<PostId>,
+    db: &mut SqliteConnection,
+) -> Result<String> {
+    if slug.chars().count() > MAX_SLUG_LENGTH {
+        return Err(Error::BadRequest(format!(
+            "Slug is too long, it can have at most {MAX_SLUG_LENGTH} characters"
+        )));
+    }
+
+    if !is_valid_slug(&slug) {
+        return Err(Error::BadRequest(String::from(
+            "Slug can only contain lowercase letters, numbers and single hyphens",
+        )));
+    }
+
+    let taken = PostModel::find_slugs_like(blog_id, &slug, current, db).await?;
+
+    if taken.iter().any(|v| v.eq_ignore_ascii_case(&slug)) {
+        return Err(Error::Conflict("Slug is already used by another post"));
+    }
+
+    Ok(slug)
+}
+
+/// Adds `-2`, `-3`, ... when another post of the blog uses or used to use the slug.
+async fn unique_post_slug(
+    blog_id: BlogId,
+    slug: &str,
+    db: &mut SqliteConnection,
+) -> Result<Option<String>> {
+    // Titles without any letters or numbers
+    if slug.is_empty() {
+        return Ok(None);
+    }
+
+    let mut base = slug;
+    let mut taken = taken_post_slugs(blog_id, base, db).await?;
+
+    let mut candidate = slug.to_string();
+    let mut suffix = 1;
+
+    while taken.iter().any(|v| v.eq_ignore_ascii_case(&candidate)) {
+        suffix += 1;
+
+        let ending = format!("-{suffix}");
+
+        // Shortened so the suffix still fits within the length limit
+        let shortened = truncate_slug(slug, MAX_SLUG_LENGTH - ending.len());
+
+        if shortened != base {
+            base = shortened;
+            taken = taken_post_slugs(blog_id, base, db).await?;
+        }
+
+        candidate = format!("{base}{ending}");
+    }
+
+    Ok(Some(candidate))
+}
+
+/// Slugs which are `slug` or `slug-{suffix}`, now or previously used by posts of the blog.
+async fn taken_post_slugs(
+    blog_id: BlogId,
+    slug: &str,
+    db: &mut SqliteConnection,
+) -> Result<Vec<String>> {
+    let mut taken = PostModel::find_slugs_like(blog_id, slug, None, db).await?;
+    taken.extend(PostSlugHistoryModel::find_slugs_like(blog_id, slug, db).await?);
+
+    Ok(taken)
+}
+
+async fn find_author(
+    blog_id: BlogId,
+    id: AuthorId,
+    db: &mut SqliteConnection,
+) -> Result<AuthorModel> {
+    let Some(author) = AuthorModel::find_one_by_id(blog_id, id, db).await? else {
+        return Err(Error::NotFound("Author not found"));
+    };
+
+    Ok(author)
+}
+
+/// Replaces the co-authors of the post, keeping their order. The main author is skipped.
+async fn set_post_co_authors(
+    post: &PostModel,
+    co_authors: Vec<AuthorId>,
+    db: &mut SqliteConnection,
+) -> Result<()> {
+    let mut ids = Vec::new();
+
+    for id in co_authors {
+        let author = find_author(post.blog_id, id, &mut *db).await?;
+
+        if Some(author.id) != post.author_id && !ids.contains(&author.id) {
+            ids.push(author.id);
+        }
+    }
+
+    PostAuthorModel::delete_by_post_id(post.blog_id, post.id, &mut *db).await?;
+
+    for (position, author_id) in ids.into_iter().enumerate() {
+        PostAuthorModel {
+            blog_id: post.blog_id,
+            post_id: post.id,
+            author_id,
+            position: position as i32,
+        }
+        .insert(&mut *db)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// A category or tag referenced either by its id or by its name.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TermRef<Id> {
+    Id(Id),
+    Name(String),
+}
+
+/// Replaces the categories of the post. Categories given by name have to exist already.
+async fn set_post_categories(
+    post: &PostModel,
+    categories: Vec<TermRef<CategoryId>>,
+    db: &mut SqliteConnection,
+) -> Result<()> {
+    let mut ids = Vec::new();
+
+    for category in categories {
+        let found = match category {
+            TermRef::Id(id) => CategoryModel::find_one_by_id(post.blog_id, id, &mut *db).await?,
+            TermRef::Name(name) => {
+                let name = name.trim();
+
+                match CategoryModel::find_one_by_name(post.blog_id, name, &mut *db).await? {
+                    Some(category) => Some(category),
+                    None => {
+                        CategoryModel::find_one_by_slug(post.blog_id, &slugify(name), &mut *db)
+                            .await?
+                    }
+                }
+            }
+        };
+
+        let Some(category) = found else {
+            return Err(Error::NotFound("Category not found"));
+        };
+
+        if !ids.contains(&category.id) {
+            ids.push(category.id);
+        }
+    }
+
+    PostCategoryModel::delete_by_post_id(post.blog_id, post.id, &mut *db).await?;
+
+    for category_id in ids {
+        PostCategoryModel {
+            blog_id: post.blog_id,
+            post_id: post.id,
+            category_id,
+        }
+        .insert(&mut *db)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Replaces the tags of the post, creating the ones given by a name that isn't in use yet.
+async fn set_post_tags(
+    post: &PostModel,
+    tags: Vec<TermRef<TagId>>,
+    db: &mut SqliteConnection,
+) -> Result<()> {
+    let mut ids = Vec::new();
+
+    for tag in tags {
+        let tag = match tag {
+            TermRef::Id(id) => {
+                let Some(tag) = TagModel::find_one_by_id(post.blog_id, id, &mut *db).await? else {
+                    return Err(Error::NotFound("Tag not found"));
+                };
+
+                tag
+            }
+
+            TermRef::Name(name) => find_or_create_tag(post.blog_id, name, &mut *db).await?,
+        };
+
+        if !ids.contains(&tag.id) {
+            ids.push(tag.id);
+        }
+    }
+
+    PostTagModel::delete_by_post_id(post.blog_id, post.id, &mut *db).await?;
+
+    for tag_id in ids {
+        PostTagModel {
+            blog_id: post.blog_id,
+            post_id: post.id,
+            tag_id,
+        }
+        .insert(&mut *db)
+        .await?;
+    }
+
+    Ok(())
+}
+
+async fn find_or_create_tag(
+    blog_id: BlogId,
+    name: String,
+    db: &mut SqliteConnection,
+) -> Result<TagModel> {
+    let name = name.trim().to_string();
+    let slug = slugify(&name);
+
+    if slug.is_empty() {
+        return Err(eyre::eyre!("Tag name is required"))?;
+    }
+
+    if let Some(tag) = TagModel::find_one_by_slug_or_alias(blog_id, &slug, &mut *db).await? {
+        return Ok(tag);
+    }
+
+    let tag = NewTagModel {
+        blog_id,
+        name,
+        slug,
+    }
+    .insert(db)
+    .await?;
+
+    Ok(tag)
+}
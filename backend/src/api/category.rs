@@ -0,0 +1,257 @@
+use webby_addon_common::{
+    AddonInstanceUuid, JsonListResponse, JsonResponse, ListResponse, WrappingResponse,
+};
+use axum::{
+    extract,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use sqlx::{SqliteConnection, SqlitePool};
+
+use super::member::Member;
+use crate::{
+    models::{slugify, AuthorRole, BlogModel, CategoryModel, NewCategoryModel},
+    BlogId, CategoryId, Error, Result,
+};
+
+pub fn routes() -> Router<SqlitePool> {
+    Router::new()
+        .route("/:instance/categories", get(get_category_list))
+        .route("/:instance/category", post(create_category))
+        .route(
+            "/:instance/category/:category_id",
+            get(get_category)
+                .post(update_category)
+                .delete(delete_category),
+        )
+}
+
+async fn get_category_list(
+    extract::Path(instance_id): extract::Path<AddonInstanceUuid>,
+    extract::State(db): extract::State<SqlitePool>,
+) -> Result<JsonListResponse<CategoryModel>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    let categories = CategoryModel::find_by_blog_id(blog.id, &mut acq).await?;
+
+    Ok(Json(WrappingResponse::okay(ListResponse::all(categories))))
+}
+
+#[derive(Deserialize)]
+struct CreateCategoryJson {
+    name: String,
+    slug: Option<String>,
+    description: Option<String>,
+    parent_id: Option<CategoryId>,
+    display_order: Option<i32>,
+}
+
+async fn create_category(
+    extract::Path(instance_id): extract::Path<AddonInstanceUuid>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+    extract::Json(CreateCategoryJson {
+        name,
+        slug,
+        description,
+        parent_id,
+        display_order,
+    }): extract::Json<CreateCategoryJson>,
+) -> Result<JsonResponse<CategoryModel>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    member.author(blog.id, &mut acq).await?;
+
+    let name = name.trim().to_string();
+
+    if name.is_empty() {
+        return Err(eyre::eyre!("Category name is required"))?;
+    }
+
+    let slug = category_slug(blog.id, slug.as_deref().unwrap_or(&name), None, &mut acq).await?;
+
+    if let Some(parent_id) = parent_id {
+        check_parent(blog.id, None, parent_id, &mut acq).await?;
+    }
+
+    let category = NewCategoryModel {
+        blog_id: blog.id,
+        parent_id,
+        name,
+        slug,
+        description: description.filter(|v| !v.trim().is_empty()),
+        display_order: display_order.unwrap_or_default(),
+    }
+    .insert(&mut acq)
+    .await?;
+
+    Ok(Json(WrappingResponse::okay(category)))
+}
+
+async fn get_category(
+    extract::Path((instance_id, category_id)): extract::Path<(AddonInstanceUuid, CategoryId)>,
+    extract::State(db): extract::State<SqlitePool>,
+) -> Result<JsonResponse<CategoryModel>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    let Some(category) = CategoryModel::find_one_by_id(blog.id, category_id, &mut acq).await?
+    else {
+        return Err(Error::NotFound("Category not found"));
+    };
+
+    Ok(Json(WrappingResponse::okay(category)))
+}
+
+#[derive(Deserialize)]
+struct UpdateCategoryJson {
+    name: Option<String>,
+    slug: Option<String>,
+    description: Option<String>,
+    /// `Some(None)` moves the category to the top level
+    #[serde(default, deserialize_with = "super::deserialize_some")]
+    parent_id: Option<Option<CategoryId>>,
+    display_order: Option<i32>,
+}
+
+async fn update_category(
+    extract::Path((instance_id, category_id)): extract::Path<(AddonInstanceUuid, CategoryId)>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+    extract::Json(UpdateCategoryJson {
+        name,
+        slug,
+        description,
+        parent_id,
+        display_order,
+    }): extract::Json<UpdateCategoryJson>,
+) -> Result<JsonResponse<CategoryModel>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    member
+        .author_with(blog.id, AuthorRole::can_edit_others, &mut acq)
+        .await?;
+
+    let Some(mut category) = CategoryModel::find_one_by_id(blog.id, category_id, &mut acq).await?
+    else {
+        return Err(Error::NotFound("Category not found"));
+    };
+
+    if let Some(name) = name {
+        let name = name.trim().to_string();
+
+        if name.is_empty() {
+            return Err(eyre::eyre!("Category name is required"))?;
+        }
+
+        category.name = name;
+    }
+
+    if let Some(slug) = slug {
+        category.slug = category_slug(blog.id, &slug, Some(category.id), &mut acq).await?;
+    }
+
+    if let Some(description) = description {
+        category.description = Some(description).filter(|v| !v.trim().is_empty());
+    }
+
+    if let Some(parent_id) = parent_id {
+        if let Some(parent_id) = parent_id {
+            check_parent(blog.id, Some(category.id), parent_id, &mut acq).await?;
+        }
+
+        category.parent_id = parent_id;
+    }
+
+    if let Some(display_order) = display_order {
+        category.display_order = display_order;
+    }
+
+    category.update(&mut acq).await?;
+
+    Ok(Json(WrappingResponse::okay(category)))
+}
+
+async fn delete_category(
+    extract::Path((instance_id, category_id)): extract::Path<(AddonInstanceUuid, CategoryId)>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+) -> Result<JsonResponse<&'static str>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    member
+        .author_with(blog.id, AuthorRole::can_edit_others, &mut acq)
+        .await?;
+
+    if CategoryModel::delete(blog.id, category_id, &mut acq).await? == 0 {
+        return Err(Error::NotFound("Category not found"));
+    }
+
+    Ok(Json(WrappingResponse::okay("success")))
+}
+
+/// Slugifies the value, making sure no other category of the blog uses it.
+async fn category_slug(
+    blog_id: BlogId,
+    value: &str,
+    current: Option<CategoryId>,
+    db: &mut SqliteConnection,
+) -> Result<String> {
+    let slug = slugify(value);
+
+    if slug.is_empty() {
+        return Err(eyre::eyre!("Category slug is empty"))?;
+    }
+
+    if let Some(found) = CategoryModel::find_one_by_slug(blog_id, &slug, db).await? {
+        if Some(found.id) != current {
+            return Err(eyre::eyre!("Category slug is already in use"))?;
+        }
+    }
+
+    Ok(slug)
+}
+
+/// The parent has to belong to the same blog and can't be the category itself or one of its descendants.
+async fn check_parent(
+    blog_id: BlogId,
+    category_id: Option<CategoryId>,
+    parent_id: CategoryId,
+    db: &mut SqliteConnection,
+) -> Result<()> {
+    let mut next = Some(parent_id);
+
+    while let Some(id) = next {
+        if Some(id) == category_id {
+            return Err(eyre::eyre!("Category can't be nested inside itself"))?;
+        }
+
+        let Some(parent) = CategoryModel::find_one_by_id(blog_id, id, &mut *db).await? else {
+            return Err(Error::NotFound("Parent category not found"));
+        };
+
+        next = parent.parent_id;
+    }
+
+    Ok(())
+}
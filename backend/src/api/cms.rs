@@ -1,26 +1,143 @@
 //! Conversions to CMS format
 
-use webby_addon_common::{JsonListResponse, ListResponse, WrappingResponse};
+use webby_addon_common::{AddonInstanceUuid, JsonListResponse, ListResponse, WrappingResponse};
 use axum::{extract, routing::get, Json, Router};
+use serde::Deserialize;
 use sqlx::SqlitePool;
-use uuid::Uuid;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime, UtcOffset};
 
-use crate::Result;
+use crate::{
+    models::{BlogModel, CommentModel, PostModel, PostQuery, PostSort, PostStatus, SortOrder},
+    Error, Result,
+};
 
 pub fn routes() -> Router<SqlitePool> {
     Router::new().route("/:instance/query", get(get_query))
 }
 
+const DEFAULT_QUERY_LIMIT: i64 = 25;
+const MAX_QUERY_LIMIT: i64 = 100;
+
+/// Only published posts are listed, so there's no status filter.
+#[derive(Deserialize)]
+struct CmsQuery {
+    category: Option<String>,
+    tag: Option<String>,
+    /// Author slug
+    author: Option<String>,
+
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    from: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    to: Option<OffsetDateTime>,
+
+    /// Comma separated sort keys. Prefix with `-` for descending. eg. `-post_date,title`
+    sort: Option<String>,
+
+    offset: Option<i64>,
+    limit: Option<i64>,
+}
+
+impl CmsQuery {
+    fn into_post_query(self) -> Result<PostQuery> {
+        let mut sort = Vec::new();
+
+        for key in self.sort.as_deref().unwrap_or("-post_date").split(',') {
+            let key = key.trim();
+
+            if key.is_empty() {
+                continue;
+            }
+
+            if let Some(key) = key.strip_prefix('-') {
+                sort.push((key.parse::<PostSort>()?, SortOrder::Desc));
+            } else {
+                sort.push((key.parse::<PostSort>()?, SortOrder::Asc));
+            }
+        }
+
+        Ok(PostQuery {
+            status: Some(PostStatus::Published),
+            category: self.category,
+            tag: self.tag,
+            author: self.author,
+            // Stored dates are UTC and compared as text, so the bounds have to be too
+            from: self.from.map(|v| v.to_offset(UtcOffset::UTC)),
+            to: self.to.map(|v| v.to_offset(UtcOffset::UTC)),
+            visible_at: Some(OffsetDateTime::now_utc()),
+            sort,
+            offset: self.offset.unwrap_or(0).max(0),
+            limit: self
+                .limit
+                .unwrap_or(DEFAULT_QUERY_LIMIT)
+                .clamp(1, MAX_QUERY_LIMIT),
+        })
+    }
+}
+
 async fn get_query(
-    extract::Path(_inst_id): extract::Path<Uuid>,
-    // extract::State(db): extract::State<SqlitePool>,
+    extract::Path(instance_id): extract::Path<AddonInstanceUuid>,
+    extract::State(db): extract::State<SqlitePool>,
+    extract::Query(query): extract::Query<CmsQuery>,
 ) -> Result<JsonListResponse<serde_json::Value>> {
-    // let mut acq = db.acquire().await?;
+    let query = query.into_post_query()?;
+
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    // Freshly installed blog - used as reference until the first post is written
+    if PostModel::count_by_blog_id(blog.id, &mut acq).await? == 0 {
+        return Ok(Json(WrappingResponse::okay(ListResponse::all(
+            default_posts(),
+        ))));
+    }
+
+    let (posts, total) = PostModel::find_by_query(blog.id, &query, &mut acq).await?;
+
+    let mut items = Vec::with_capacity(posts.len());
 
-    // let found = PostModel::find_by_blog_id(BlogId::from(0), &mut *acq).await?;
+    for post in posts {
+        let comment_count =
+            CommentModel::count_approved_by_post_id(post.blog_id, post.id, &mut acq).await?;
+
+        items.push(post_to_cms(&blog, &post, comment_count)?);
+    }
+
+    Ok(Json(WrappingResponse::okay(ListResponse {
+        offset: query.offset,
+        limit: query.limit,
+        total,
+        items,
+    })))
+}
+
+fn post_to_cms(
+    blog: &BlogModel,
+    post: &PostModel,
+    comment_count: i64,
+) -> Result<serde_json::Value> {
+    Ok(serde_json::json!({
+        "_id": post.id.to_string(),
+        "_owner": blog.external_member_id.to_string(),
+        "_createdAt": post.created_at.format(&Rfc3339)?,
+        "_updatedAt": post.updated_at.format(&Rfc3339)?,
+        "content": post.content.0.to_string(),
+        "title": post.title,
+        "subtitle": post.display_excerpt(),
+        "readTime": post.read_minutes,
+        "wordCount": post.word_count,
+        "views": 0,
+        "likes": 0,
+        "commentCount": comment_count,
+        // TODO: Image Avatar, Author Name, Date
+    }))
+}
 
-    // TODO: Default values - used as reference when plugin is installed
-    Ok(Json(WrappingResponse::okay(ListResponse::all(vec![
+fn default_posts() -> Vec<serde_json::Value> {
+    vec![
         serde_json::json!({
             "_id": "0",
             "_owner": "0",
@@ -32,7 +149,6 @@ async fn get_query(
             "views": 0,
             "likes": 0,
             "commentCount": 0,
-            // TODO: Image Avatar, Author Name, Date, Read Time
         }),
         serde_json::json!({
             "_id": "1",
@@ -58,5 +174,5 @@ async fn get_query(
             "likes": 0,
             "commentCount": 0,
         }),
-    ]))))
+    ]
 }
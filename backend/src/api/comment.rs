@@ -0,0 +1,811 @@
+use webby_addon_common::{
+    AddonInstanceUuid, JsonListResponse, JsonResponse, ListResponse, WrappingResponse,
+};
+use axum::{
+    extract,
+    routing::{get, post},
+    Json, Router,
+};
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use sqlx::SqlitePool;
+
+use time::OffsetDateTime;
+
+use super::member::{Member, OptionalMember};
+use crate::{
+    models::{
+        AuthorRole, BlogModel, CommentFilter, CommentModel, CommentSettingsModel, CommentStatus,
+        NewCommentModel, PostModel, SpamBlocklistModel,
+    },
+    spam::{self, SpamInput},
+    CommentId, Error, PostId, Result,
+};
+
+const MAX_AUTHOR_NAME_LENGTH: usize = 100;
+const MAX_COMMENT_LENGTH: usize = 5_000;
+
+/// Upper bound for the max depth setting
+const MAX_COMMENT_DEPTH: i32 = 10;
+
+const DEFAULT_COMMENT_LIMIT: i64 = 25;
+const MAX_COMMENT_LIMIT: i64 = 100;
+
+/// Reader facing routes, rate limited in `api::serve`
+pub fn public_routes() -> Router<SqlitePool> {
+    Router::new()
+        .route(
+            "/:instance/post/:post_id/comments",
+            get(get_post_comments).post(create_comment),
+        )
+        .route(
+            "/:instance/post/:post_id/comments/token",
+            get(get_comment_token),
+        )
+        .route(
+            "/:instance/post/:post_id/comment/:comment_id",
+            post(edit_own_comment),
+        )
+}
+
+/// Dashboard routes
+pub fn routes() -> Router<SqlitePool> {
+    Router::new()
+        .route("/:instance/comments", get(get_comment_list))
+        .route(
+            "/:instance/comments/settings",
+            get(get_comment_settings).post(update_comment_settings),
+        )
+        .route(
+            "/:instance/comments/blocklist",
+            get(get_blocklist).post(update_blocklist),
+        )
+        .route(
+            "/:instance/comment/:comment_id",
+            get(get_comment).post(update_comment).delete(delete_comment),
+        )
+        .route(
+            "/:instance/comment/:comment_id/approve",
+            post(approve_comment),
+        )
+        .route("/:instance/comment/:comment_id/deny", post(deny_comment))
+}
+
+/// Comment fields which are safe to show to readers.
+fn public_comment(comment: &CommentModel) -> serde_json::Value {
+    serde_json::json!({
+        "id": comment.id,
+        "post_id": comment.post_id,
+        "parent_id": comment.parent_id,
+        "author_name": comment.author_name,
+        "comment": comment.comment,
+        "created_at": comment.created_at,
+        "edited_at": comment.edited_at,
+    })
+}
+
+fn is_visible(comment: &CommentModel) -> bool {
+    matches!(comment.status, CommentStatus::Approved) && comment.deleted_at.is_none()
+}
+
+/// Nests the comments under their parents.
+///
+/// Hidden comments (pending, denied or deleted) are left out unless they have visible replies,
+/// in which case a "deleted" placeholder keeps the replies attached.
+fn comment_tree(comments: &[CommentModel]) -> Vec<serde_json::Value> {
+    let mut children: HashMap<Option<CommentId>, Vec<&CommentModel>> = HashMap::new();
+
+    for comment in comments {
+        children.entry(comment.parent_id).or_default().push(comment);
+    }
+
+    fn build(
+        parent_id: Option<CommentId>,
+        children: &HashMap<Option<CommentId>, Vec<&CommentModel>>,
+    ) -> Vec<serde_json::Value> {
+        let Some(comments) = children.get(&parent_id) else {
+            return Vec::new();
+        };
+
+        let mut items = Vec::new();
+
+        for comment in comments {
+            let replies = build(Some(comment.id), children);
+
+            if is_visible(comment) {
+                let mut value = public_comment(comment);
+                value["deleted"] = false.into();
+                value["replies"] = replies.into();
+                items.push(value);
+            } else if !replies.is_empty() {
+                items.push(serde_json::json!({
+                    "id": comment.id,
+                    "post_id": comment.post_id,
+                    "parent_id": comment.parent_id,
+                    "deleted": true,
+                    "replies": replies,
+                }));
+            }
+        }
+
+        items
+    }
+
+    build(None, &children)
+}
+
+async fn get_post_comments(
+    extract::Path((instance_id, post_id)): extract::Path<(AddonInstanceUuid, PostId)>,
+    extract::State(db): extract::State<SqlitePool>,
+) -> Result<JsonListResponse<serde_json::Value>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    let Some(post) = PostModel::find_one_by_id(blog.id, post_id, &mut acq)
+        .await?
+        .filter(|post| post.is_public())
+    else {
+        return Err(Error::NotFound("Post not found"));
+    };
+
+    let comments = CommentModel::find_by_post_id(post.blog_id, post.id, &mut acq).await?;
+
+    Ok(Json(WrappingResponse::okay(ListResponse::all(
+        comment_tree(&comments),
+    ))))
+}
+
+/// Form token to send along with the comment, and whether the form should be shown.
+/// Loaded when the comment form is shown.
+async fn get_comment_token(
+    extract::Path((instance_id, post_id)): extract::Path<(AddonInstanceUuid, PostId)>,
+    extract::State(db): extract::State<SqlitePool>,
+) -> Result<JsonResponse<serde_json::Value>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    let Some(post) = PostModel::find_one_by_id(blog.id, post_id, &mut acq)
+        .await?
+        .filter(|post| post.is_public())
+    else {
+        return Err(Error::NotFound("Post not found"));
+    };
+
+    let now = OffsetDateTime::now_utc();
+    let settings = CommentSettingsModel::find_one_by_blog_id(blog.id, &mut acq).await?;
+
+    Ok(Json(WrappingResponse::okay(serde_json::json!({
+        "token": spam::token::issue(post.id, now),
+        "open": settings.is_open_for(&post, now),
+        "require_email": settings.require_email,
+    }))))
+}
+
+#[derive(Deserialize)]
+struct CreateCommentJson {
+    /// Comment being replied to
+    parent_id: Option<CommentId>,
+    author_name: String,
+    email: Option<String>,
+    comment: String,
+    /// Honeypot - hidden from readers
+    website: Option<String>,
+    /// From the comment token route
+    token: Option<String>,
+}
+
+/// Submitted comments wait in the moderation queue until approved.
+async fn create_comment(
+    extract::Path((instance_id, post_id)): extract::Path<(AddonInstanceUuid, PostId)>,
+    extract::State(db): extract::State<SqlitePool>,
+    OptionalMember(member): OptionalMember,
+    extract::Json(CreateCommentJson {
+        parent_id,
+        author_name,
+        email,
+        comment,
+        website,
+        token,
+    }): extract::Json<CreateCommentJson>,
+) -> Result<JsonResponse<serde_json::Value>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    let Some(post) = PostModel::find_one_by_id(blog.id, post_id, &mut acq)
+        .await?
+        .filter(|post| post.is_public())
+    else {
+        return Err(Error::NotFound("Post not found"));
+    };
+
+    let now = OffsetDateTime::now_utc();
+    let settings = CommentSettingsModel::find_one_by_blog_id(blog.id, &mut acq).await?;
+
+    if !settings.is_open_for(&post, now) {
+        return Err(eyre::eyre!("Comments are closed"))?;
+    }
+
+    let author_name = author_name.trim().to_string();
+    let comment = comment.trim().to_string();
+    let email = email
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+
+    check_author_name(&author_name)?;
+    check_comment(&comment)?;
+
+    if settings.require_email && email.is_none() {
+        return Err(eyre::eyre!("Email address is required"))?;
+    }
+
+    if email.as_deref().is_some_and(|v| !is_email(v)) {
+        return Err(eyre::eyre!("Invalid email address"))?;
+    }
+
+    let depth = match parent_id {
+        Some(parent_id) => {
+            let Some(parent) = CommentModel::find_one_by_id(blog.id, parent_id, &mut acq)
+                .await?
+                .filter(|parent| parent.post_id == post.id && is_visible(parent))
+            else {
+                return Err(Error::NotFound("Parent comment not found"));
+            };
+
+            if parent.depth + 1 > settings.max_depth {
+                return Err(eyre::eyre!("Replies can't be nested any deeper"))?;
+            }
+
+            parent.depth + 1
+        }
+
+        None => 0,
+    };
+
+    let verdict = spam::run_checks(
+        &spam::default_checks(),
+        &SpamInput {
+            blog_id: blog.id,
+            post: &post,
+            author_name: &author_name,
+            email: email.as_deref(),
+            comment: &comment,
+            honeypot: website.as_deref(),
+            token: token.as_deref(),
+            submitted_at: now,
+        },
+        &mut acq,
+    )
+    .await?;
+
+    let status = if verdict.is_spam() {
+        CommentStatus::Spam
+    } else if member.is_some() && settings.auto_approve_members && verdict.signals.is_empty() {
+        CommentStatus::Approved
+    } else {
+        CommentStatus::Pending
+    };
+
+    let comment = NewCommentModel {
+        blog_id: blog.id,
+        post_id: post.id,
+        parent_id,
+        depth,
+        external_member_id: member,
+        author_name,
+        email,
+        comment,
+        status,
+        spam_score: verdict.score,
+        spam_reasons: verdict.signals,
+    }
+    .insert(&mut acq)
+    .await?;
+
+    // Spam is reported as pending so the sender can't tell it was caught
+    let status = if matches!(comment.status, CommentStatus::Approved) {
+        CommentStatus::Approved
+    } else {
+        CommentStatus::Pending
+    };
+
+    Ok(Json(WrappingResponse::okay(serde_json::json!({
+        "id": comment.id,
+        "status": status,
+    }))))
+}
+
+#[derive(Deserialize)]
+struct EditOwnCommentJson {
+    comment: String,
+}
+
+/// Lets signed in members fix up their own comments.
+async fn edit_own_comment(
+    extract::Path((instance_id, post_id, comment_id)): extract::Path<(
+        AddonInstanceUuid,
+        PostId,
+        CommentId,
+    )>,
+    extract::State(db): extract::State<SqlitePool>,
+    OptionalMember(member): OptionalMember,
+    extract::Json(EditOwnCommentJson { comment: text }): extract::Json<EditOwnCommentJson>,
+) -> Result<JsonResponse<serde_json::Value>> {
+    let mut tx = db.begin().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut tx).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    let Some(member) = member else {
+        return Err(eyre::eyre!(
+            "Only signed in members can edit their comments"
+        ))?;
+    };
+
+    let Some(mut comment) = CommentModel::find_one_by_id(blog.id, comment_id, &mut tx)
+        .await?
+        .filter(|comment| {
+            comment.post_id == post_id
+                && comment.deleted_at.is_none()
+                && comment.external_member_id == Some(member)
+        })
+    else {
+        return Err(Error::NotFound("Comment not found"));
+    };
+
+    let Some(post) = PostModel::find_one_by_id(blog.id, post_id, &mut tx).await? else {
+        return Err(Error::NotFound("Post not found"));
+    };
+
+    let text = text.trim().to_string();
+    check_comment(&text)?;
+
+    // The form checks already passed when the comment was submitted
+    let verdict = spam::run_checks(
+        &spam::content_checks(),
+        &SpamInput {
+            blog_id: blog.id,
+            post: &post,
+            author_name: &comment.author_name,
+            email: comment.email.as_deref(),
+            comment: &text,
+            honeypot: None,
+            token: None,
+            submitted_at: OffsetDateTime::now_utc(),
+        },
+        &mut tx,
+    )
+    .await?;
+
+    // Approved comments go back to moderation unless the new text is clean
+    comment.status = match comment.status {
+        CommentStatus::Denied => CommentStatus::Denied,
+        _ if verdict.is_spam() => CommentStatus::Spam,
+        CommentStatus::Approved if !verdict.signals.is_empty() => CommentStatus::Pending,
+        status => status,
+    };
+    comment.spam_score = verdict.score;
+    comment.spam_reasons = Some(sqlx::types::Json(verdict.signals));
+
+    spam::untrain(&mut comment, &mut tx).await?;
+
+    comment.comment = text;
+    comment.update(&mut tx).await?;
+    comment.update_spam(&mut tx).await?;
+
+    tx.commit().await?;
+
+    Ok(Json(WrappingResponse::okay(public_comment(&comment))))
+}
+
+#[derive(Deserialize)]
+struct CommentListQuery {
+    status: Option<CommentStatus>,
+    post_id: Option<PostId>,
+    offset: Option<i64>,
+    limit: Option<i64>,
+}
+
+async fn get_comment_list(
+    extract::Path(instance_id): extract::Path<AddonInstanceUuid>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+    extract::Query(CommentListQuery {
+        status,
+        post_id,
+        offset,
+        limit,
+    }): extract::Query<CommentListQuery>,
+) -> Result<JsonListResponse<CommentModel>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    member
+        .author_with(blog.id, AuthorRole::can_moderate, &mut acq)
+        .await?;
+
+    let offset = offset.unwrap_or(0).max(0);
+    let limit = limit
+        .unwrap_or(DEFAULT_COMMENT_LIMIT)
+        .clamp(1, MAX_COMMENT_LIMIT);
+
+    let (items, total) = CommentModel::find_by_blog_id(
+        blog.id,
+        &CommentFilter { status, post_id },
+        offset,
+        limit,
+        &mut acq,
+    )
+    .await?;
+
+    Ok(Json(WrappingResponse::okay(ListResponse {
+        offset,
+        limit,
+        total,
+        items,
+    })))
+}
+
+async fn get_comment(
+    extract::Path((instance_id, comment_id)): extract::Path<(AddonInstanceUuid, CommentId)>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+) -> Result<JsonResponse<CommentModel>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    member
+        .author_with(blog.id, AuthorRole::can_moderate, &mut acq)
+        .await?;
+
+    let Some(comment) = CommentModel::find_one_by_id(blog.id, comment_id, &mut acq).await? else {
+        return Err(Error::NotFound("Comment not found"));
+    };
+
+    Ok(Json(WrappingResponse::okay(comment)))
+}
+
+#[derive(Deserialize)]
+struct UpdateCommentJson {
+    author_name: Option<String>,
+    comment: Option<String>,
+}
+
+async fn update_comment(
+    extract::Path((instance_id, comment_id)): extract::Path<(AddonInstanceUuid, CommentId)>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+    extract::Json(UpdateCommentJson {
+        author_name,
+        comment: text,
+    }): extract::Json<UpdateCommentJson>,
+) -> Result<JsonResponse<CommentModel>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    member
+        .author_with(blog.id, AuthorRole::can_moderate, &mut acq)
+        .await?;
+
+    let Some(mut comment) = CommentModel::find_one_by_id(blog.id, comment_id, &mut acq)
+        .await?
+        .filter(|comment| comment.deleted_at.is_none())
+    else {
+        return Err(Error::NotFound("Comment not found"));
+    };
+
+    if author_name.is_some() || text.is_some() {
+        spam::untrain(&mut comment, &mut acq).await?;
+    }
+
+    if let Some(author_name) = author_name {
+        let author_name = author_name.trim().to_string();
+        check_author_name(&author_name)?;
+
+        comment.author_name = author_name;
+    }
+
+    if let Some(text) = text {
+        let text = text.trim().to_string();
+        check_comment(&text)?;
+
+        comment.comment = text;
+    }
+
+    comment.update(&mut acq).await?;
+
+    Ok(Json(WrappingResponse::okay(comment)))
+}
+
+async fn approve_comment(
+    extract::Path((instance_id, comment_id)): extract::Path<(AddonInstanceUuid, CommentId)>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+) -> Result<JsonResponse<&'static str>> {
+    moderate_comment(instance_id, comment_id, member, CommentStatus::Approved, db).await
+}
+
+async fn deny_comment(
+    extract::Path((instance_id, comment_id)): extract::Path<(AddonInstanceUuid, CommentId)>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+) -> Result<JsonResponse<&'static str>> {
+    moderate_comment(instance_id, comment_id, member, CommentStatus::Denied, db).await
+}
+
+async fn moderate_comment(
+    instance_id: AddonInstanceUuid,
+    comment_id: CommentId,
+    member: Member,
+    status: CommentStatus,
+    db: SqlitePool,
+) -> Result<JsonResponse<&'static str>> {
+    let mut tx = db.begin().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut tx).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    member
+        .author_with(blog.id, AuthorRole::can_moderate, &mut tx)
+        .await?;
+
+    let Some(comment) = CommentModel::find_one_by_id(blog.id, comment_id, &mut tx)
+        .await?
+        .filter(|comment| comment.deleted_at.is_none())
+    else {
+        return Err(Error::NotFound("Comment not found"));
+    };
+
+    CommentModel::update_status(blog.id, comment.id, status, &mut tx).await?;
+
+    // Moderator decisions train the spam classifier
+    match status {
+        CommentStatus::Approved => spam::train(&comment, false, &mut tx).await?,
+        CommentStatus::Denied => spam::train(&comment, true, &mut tx).await?,
+        CommentStatus::Pending | CommentStatus::Spam => (),
+    }
+
+    tx.commit().await?;
+
+    Ok(Json(WrappingResponse::okay("success")))
+}
+
+#[derive(Deserialize)]
+struct DeleteCommentQuery {
+    reason: Option<String>,
+}
+
+async fn delete_comment(
+    extract::Path((instance_id, comment_id)): extract::Path<(AddonInstanceUuid, CommentId)>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+    extract::Query(DeleteCommentQuery { reason }): extract::Query<DeleteCommentQuery>,
+) -> Result<JsonResponse<&'static str>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    member
+        .author_with(blog.id, AuthorRole::can_moderate, &mut acq)
+        .await?;
+
+    let Some(comment) = CommentModel::find_one_by_id(blog.id, comment_id, &mut acq)
+        .await?
+        .filter(|comment| comment.deleted_at.is_none())
+    else {
+        return Err(Error::NotFound("Comment not found"));
+    };
+
+    CommentModel::delete(
+        blog.id,
+        comment.id,
+        reason.filter(|v| !v.trim().is_empty()),
+        &mut acq,
+    )
+    .await?;
+
+    Ok(Json(WrappingResponse::okay("success")))
+}
+
+async fn get_comment_settings(
+    extract::Path(instance_id): extract::Path<AddonInstanceUuid>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+) -> Result<JsonResponse<CommentSettingsModel>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    member
+        .author_with(blog.id, AuthorRole::can_moderate, &mut acq)
+        .await?;
+
+    let settings = CommentSettingsModel::find_one_by_blog_id(blog.id, &mut acq).await?;
+
+    Ok(Json(WrappingResponse::okay(settings)))
+}
+
+#[derive(Deserialize)]
+struct UpdateCommentSettingsJson {
+    max_depth: Option<i32>,
+    enabled: Option<bool>,
+    auto_approve_members: Option<bool>,
+    /// `Some(None)` keeps comments open indefinitely
+    #[serde(default, deserialize_with = "super::deserialize_some")]
+    close_after_days: Option<Option<i32>>,
+    require_email: Option<bool>,
+}
+
+async fn update_comment_settings(
+    extract::Path(instance_id): extract::Path<AddonInstanceUuid>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+    extract::Json(UpdateCommentSettingsJson {
+        max_depth,
+        enabled,
+        auto_approve_members,
+        close_after_days,
+        require_email,
+    }): extract::Json<UpdateCommentSettingsJson>,
+) -> Result<JsonResponse<CommentSettingsModel>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    member
+        .author_with(blog.id, AuthorRole::can_moderate, &mut acq)
+        .await?;
+
+    let mut settings = CommentSettingsModel::find_one_by_blog_id(blog.id, &mut acq).await?;
+
+    if let Some(max_depth) = max_depth {
+        if !(0..=MAX_COMMENT_DEPTH).contains(&max_depth) {
+            return Err(eyre::eyre!(
+                "Max depth must be between 0 and {MAX_COMMENT_DEPTH}"
+            ))?;
+        }
+
+        settings.max_depth = max_depth;
+    }
+
+    if let Some(close_after_days) = close_after_days {
+        if close_after_days.is_some_and(|v| v < 1) {
+            return Err(eyre::eyre!("Comments must stay open for at least 1 day"))?;
+        }
+
+        settings.close_after_days = close_after_days;
+    }
+
+    if let Some(enabled) = enabled {
+        settings.enabled = enabled;
+    }
+
+    if let Some(auto_approve_members) = auto_approve_members {
+        settings.auto_approve_members = auto_approve_members;
+    }
+
+    if let Some(require_email) = require_email {
+        settings.require_email = require_email;
+    }
+
+    settings.save(&mut acq).await?;
+
+    Ok(Json(WrappingResponse::okay(settings)))
+}
+
+async fn get_blocklist(
+    extract::Path(instance_id): extract::Path<AddonInstanceUuid>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+) -> Result<JsonListResponse<SpamBlocklistModel>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    member
+        .author_with(blog.id, AuthorRole::can_moderate, &mut acq)
+        .await?;
+
+    let words = SpamBlocklistModel::find_by_blog_id(blog.id, &mut acq).await?;
+
+    Ok(Json(WrappingResponse::okay(ListResponse::all(words))))
+}
+
+#[derive(Deserialize)]
+struct UpdateBlocklistJson {
+    words: Vec<String>,
+}
+
+/// Replaces the blocklist. Comments containing any of the words are flagged as spam.
+async fn update_blocklist(
+    extract::Path(instance_id): extract::Path<AddonInstanceUuid>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+    extract::Json(UpdateBlocklistJson { words }): extract::Json<UpdateBlocklistJson>,
+) -> Result<JsonListResponse<SpamBlocklistModel>> {
+    let mut tx = db.begin().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut tx).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    member
+        .author_with(blog.id, AuthorRole::can_moderate, &mut tx)
+        .await?;
+
+    let words = words
+        .into_iter()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .collect::<Vec<_>>();
+
+    SpamBlocklistModel::replace(blog.id, &words, &mut tx).await?;
+
+    let words = SpamBlocklistModel::find_by_blog_id(blog.id, &mut tx).await?;
+
+    tx.commit().await?;
+
+    Ok(Json(WrappingResponse::okay(ListResponse::all(words))))
+}
+
+fn check_author_name(value: &str) -> Result<()> {
+    if value.is_empty() || value.chars().count() > MAX_AUTHOR_NAME_LENGTH {
+        return Err(
+            eyre::eyre!("Name must be between 1 and {MAX_AUTHOR_NAME_LENGTH} characters").into(),
+        );
+    }
+
+    Ok(())
+}
+
+fn check_comment(value: &str) -> Result<()> {
+    if value.is_empty() || value.chars().count() > MAX_COMMENT_LENGTH {
+        return Err(
+            eyre::eyre!("Comment must be between 1 and {MAX_COMMENT_LENGTH} characters").into(),
+        );
+    }
+
+    Ok(())
+}
+
+/// Loose check - something before and after a single `@`, with a dot in the domain.
+fn is_email(value: &str) -> bool {
+    let Some((local, domain)) = value.split_once('@') else {
+        return false;
+    };
+
+    !local.is_empty()
+        && !domain.contains('@')
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !value.chars().any(char::is_whitespace)
+}
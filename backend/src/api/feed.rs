@@ -0,0 +1,493 @@
+//! Public RSS 2.0 and Atom feeds
+
+use webby_addon_common::AddonInstanceUuid;
+use std::fmt::Write;
+
+use axum::{
+    extract,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use sha2::{Digest, Sha256};
+use sqlx::{SqliteConnection, SqlitePool};
+use time::{
+    format_description::{well_known::Rfc3339, FormatItem},
+    macros::format_description,
+    OffsetDateTime,
+};
+
+use super::proxy::ProxiedHeaders;
+use crate::{
+    delta::{self, html::escape, Delta},
+    models::{
+        AuthorModel, BlogModel, CategoryModel, PostModel, PostQuery, PostSort, PostStatus,
+        SortOrder, TagModel,
+    },
+    Error, Result,
+};
+
+const FEED_ITEM_COUNT: i64 = 20;
+
+/// Path of a post on the website, relative to the origin the feed was requested from.
+const POST_PATH: &str = "/post";
+
+/// RFC 7231 IMF-fixdate. Also valid as an RFC 822 date for RSS.
+const HTTP_DATE: &[FormatItem<'static>] = format_description!(
+    "[weekday repr:short], [day] [month repr:short] [year] [hour]:[minute]:[second] GMT"
+);
+
+pub fn routes() -> Router<SqlitePool> {
+    Router::new()
+        .route("/:instance/feed.rss", get(get_rss_feed))
+        .route("/:instance/feed.atom", get(get_atom_feed))
+        .route(
+            "/:instance/category/:slug/feed.rss",
+            get(get_category_rss_feed),
+        )
+        .route(
+            "/:instance/category/:slug/feed.atom",
+            get(get_category_atom_feed),
+        )
+        .route("/:instance/tag/:slug/feed.rss", get(get_tag_rss_feed))
+        .route("/:instance/tag/:slug/feed.atom", get(get_tag_atom_feed))
+}
+
+#[derive(Clone, Copy)]
+pub enum FeedFormat {
+    Rss,
+    Atom,
+}
+
+pub struct Feed {
+    pub title: String,
+    pub description: String,
+    /// Website URL
+    pub link: String,
+    /// URL of the feed itself
+    pub self_link: String,
+    pub updated: OffsetDateTime,
+    pub items: Vec<FeedItem>,
+}
+
+pub struct FeedItem {
+    pub id: String,
+    pub title: String,
+    pub link: String,
+    pub author: String,
+    pub summary: String,
+    pub html: String,
+    pub categories: Vec<String>,
+    pub published: OffsetDateTime,
+    pub updated: OffsetDateTime,
+}
+
+async fn get_rss_feed(
+    extract::Path(instance_id): extract::Path<AddonInstanceUuid>,
+    extract::State(db): extract::State<SqlitePool>,
+    extract::OriginalUri(uri): extract::OriginalUri,
+    ProxiedHeaders(headers): ProxiedHeaders,
+) -> Result<Response> {
+    blog_feed(instance_id, FeedFormat::Rss, uri.path(), &headers, &db).await
+}
+
+async fn get_atom_feed(
+    extract::Path(instance_id): extract::Path<AddonInstanceUuid>,
+    extract::State(db): extract::State<SqlitePool>,
+    extract::OriginalUri(uri): extract::OriginalUri,
+    ProxiedHeaders(headers): ProxiedHeaders,
+) -> Result<Response> {
+    blog_feed(instance_id, FeedFormat::Atom, uri.path(), &headers, &db).await
+}
+
+async fn get_category_rss_feed(
+    extract::Path((instance_id, slug)): extract::Path<(AddonInstanceUuid, String)>,
+    extract::State(db): extract::State<SqlitePool>,
+    extract::OriginalUri(uri): extract::OriginalUri,
+    ProxiedHeaders(headers): ProxiedHeaders,
+) -> Result<Response> {
+    let scope = FeedScope::Category(slug);
+
+    scoped_feed(
+        instance_id,
+        scope,
+        FeedFormat::Rss,
+        uri.path(),
+        &headers,
+        &db,
+    )
+    .await
+}
+
+async fn get_category_atom_feed(
+    extract::Path((instance_id, slug)): extract::Path<(AddonInstanceUuid, String)>,
+    extract::State(db): extract::State<SqlitePool>,
+    extract::OriginalUri(uri): extract::OriginalUri,
+    ProxiedHeaders(headers): ProxiedHeaders,
+) -> Result<Response> {
+    let scope = FeedScope::Category(slug);
+
+    scoped_feed(
+        instance_id,
+        scope,
+        FeedFormat::Atom,
+        uri.path(),
+        &headers,
+        &db,
+    )
+    .await
+}
+
+async fn get_tag_rss_feed(
+    extract::Path((instance_id, slug)): extract::Path<(AddonInstanceUuid, String)>,
+    extract::State(db): extract::State<SqlitePool>,
+    extract::OriginalUri(uri): extract::OriginalUri,
+    ProxiedHeaders(headers): ProxiedHeaders,
+) -> Result<Response> {
+    let scope = FeedScope::Tag(slug);
+
+    scoped_feed(
+        instance_id,
+        scope,
+        FeedFormat::Rss,
+        uri.path(),
+        &headers,
+        &db,
+    )
+    .await
+}
+
+async fn get_tag_atom_feed(
+    extract::Path((instance_id, slug)): extract::Path<(AddonInstanceUuid, String)>,
+    extract::State(db): extract::State<SqlitePool>,
+    extract::OriginalUri(uri): extract::OriginalUri,
+    ProxiedHeaders(headers): ProxiedHeaders,
+) -> Result<Response> {
+    let scope = FeedScope::Tag(slug);
+
+    scoped_feed(
+        instance_id,
+        scope,
+        FeedFormat::Atom,
+        uri.path(),
+        &headers,
+        &db,
+    )
+    .await
+}
+
+enum FeedScope {
+    Category(String),
+    Tag(String),
+}
+
+async fn scoped_feed(
+    instance_id: AddonInstanceUuid,
+    scope: FeedScope,
+    format: FeedFormat,
+    path: &str,
+    headers: &HeaderMap,
+    db: &SqlitePool,
+) -> Result<Response> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    let feed = match scope {
+        FeedScope::Category(slug) => {
+            let Some(category) = CategoryModel::find_one_by_slug(blog.id, &slug, &mut acq).await?
+            else {
+                return Err(Error::NotFound("Category not found"));
+            };
+
+            let title = format!("{} - {}", blog.name, category.name);
+
+            build_feed(
+                &blog,
+                title,
+                Some(category.slug),
+                None,
+                path,
+                headers,
+                &mut acq,
+            )
+            .await?
+        }
+
+        FeedScope::Tag(slug) => {
+            let Some(tag) = TagModel::find_one_by_slug_or_alias(blog.id, &slug, &mut acq).await?
+            else {
+                return Err(Error::NotFound("Tag not found"));
+            };
+
+            let title = format!("{} - {}", blog.name, tag.name);
+
+            build_feed(&blog, title, None, Some(tag.slug), path, headers, &mut acq).await?
+        }
+    };
+
+    Ok(feed_response(&feed, format, headers))
+}
+
+async fn blog_feed(
+    instance_id: AddonInstanceUuid,
+    format: FeedFormat,
+    path: &str,
+    headers: &HeaderMap,
+    db: &SqlitePool,
+) -> Result<Response> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    let feed = build_feed(
+        &blog,
+        blog.name.clone(),
+        None,
+        None,
+        path,
+        headers,
+        &mut acq,
+    )
+    .await?;
+
+    Ok(feed_response(&feed, format, headers))
+}
+
+/// Latest published posts of the blog, optionally limited to a single category or tag.
+pub async fn build_feed(
+    blog: &BlogModel,
+    title: String,
+    category: Option<String>,
+    tag: Option<String>,
+    path: &str,
+    headers: &HeaderMap,
+    db: &mut SqliteConnection,
+) -> Result<Feed> {
+    let now = OffsetDateTime::now_utc();
+    let origin = request_origin(headers);
+
+    let query = PostQuery {
+        status: Some(PostStatus::Published),
+        category,
+        tag,
+        author: None,
+        from: None,
+        to: None,
+        visible_at: Some(now),
+        sort: vec![(PostSort::PostDate, SortOrder::Desc)],
+        offset: 0,
+        limit: FEED_ITEM_COUNT,
+    };
+
+    let (posts, _) = PostModel::find_by_query(blog.id, &query, &mut *db).await?;
+
+    let mut items = Vec::with_capacity(posts.len());
+
+    for post in posts {
+        let categories = CategoryModel::find_by_post_id(post.blog_id, post.id, &mut *db)
+            .await?
+            .into_iter()
+            .map(|v| v.name)
+            .collect();
+
+        let author = match post.author_id {
+            Some(id) => AuthorModel::find_one_by_id(blog.id, id, &mut *db).await?,
+            None => None,
+        };
+
+        let path = match post.slug.as_deref() {
+            Some(slug) => format!("{POST_PATH}/{slug}"),
+            None => format!("{POST_PATH}/{}", post.id),
+        };
+
+        items.push(FeedItem {
+            id: format!("{origin}{POST_PATH}/{}", post.id),
+            link: format!("{origin}{path}"),
+            author: author.map_or_else(|| blog.name.clone(), |v| v.name),
+            summary: post.display_excerpt().to_string(),
+            html: delta::html::render(&Delta::from_value(&post.content)),
+            categories,
+            published: post.post_date,
+            updated: post.updated_at,
+            title: post.title,
+        });
+    }
+
+    let updated = items
+        .iter()
+        .map(|item| item.updated)
+        .max()
+        .unwrap_or(blog.updated_at);
+
+    Ok(Feed {
+        description: format!("Latest posts from {}", blog.name),
+        link: origin.clone(),
+        self_link: format!("{origin}{path}"),
+        updated,
+        items,
+        title,
+    })
+}
+
+/// Renders the feed, answering with `304 Not Modified` if the reader already has it.
+pub fn feed_response(feed: &Feed, format: FeedFormat, headers: &HeaderMap) -> Response {
+    let (body, content_type) = match format {
+        FeedFormat::Rss => (render_rss(feed), "application/rss+xml; charset=utf-8"),
+        FeedFormat::Atom => (render_atom(feed), "application/atom+xml; charset=utf-8"),
+    };
+
+    let etag = format!("\"{:x}\"", Sha256::digest(body.as_bytes()));
+
+    // No Last-Modified, as the newest listed post can be unpublished or deleted which would move
+    // it back in time, and readers only sending If-Modified-Since would miss the change.
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.split(',').any(|v| v.trim() == etag || v.trim() == "*"));
+
+    let mut resp = if not_modified {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        let mut resp = body.into_response();
+
+        resp.headers_mut()
+            .insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+
+        resp
+    };
+
+    let headers = resp.headers_mut();
+
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        headers.insert(header::ETAG, value);
+    }
+
+    headers.insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static("public, max-age=300"),
+    );
+
+    resp
+}
+
+fn render_rss(feed: &Feed) -> String {
+    let mut out = String::new();
+
+    out.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    out.push_str(r#"<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:content="http://purl.org/rss/1.0/modules/content/">"#);
+    out.push_str("<channel>");
+
+    let _ = write!(
+        out,
+        r#"<title>{}</title><link>{}</link><description>{}</description><lastBuildDate>{}</lastBuildDate><atom:link href="{}" rel="self" type="application/rss+xml"/>"#,
+        escape(&feed.title),
+        escape(&feed.link),
+        escape(&feed.description),
+        http_date(feed.updated),
+        escape(&feed.self_link),
+    );
+
+    for item in &feed.items {
+        let _ = write!(
+            out,
+            r#"<item><title>{}</title><link>{}</link><guid isPermaLink="false">{}</guid><pubDate>{}</pubDate><dc:creator>{}</dc:creator><description>{}</description><content:encoded>{}</content:encoded>"#,
+            escape(&item.title),
+            escape(&item.link),
+            escape(&item.id),
+            http_date(item.published),
+            escape(&item.author),
+            escape(&item.summary),
+            escape(&item.html),
+        );
+
+        for category in &item.categories {
+            let _ = write!(out, "<category>{}</category>", escape(category));
+        }
+
+        out.push_str("</item>");
+    }
+
+    out.push_str("</channel></rss>");
+
+    out
+}
+
+fn render_atom(feed: &Feed) -> String {
+    let mut out = String::new();
+
+    out.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    out.push_str(r#"<feed xmlns="http://www.w3.org/2005/Atom">"#);
+
+    let _ = write!(
+        out,
+        r#"<id>{}</id><title>{}</title><subtitle>{}</subtitle><link href="{}"/><link href="{}" rel="self"/><updated>{}</updated>"#,
+        escape(&feed.self_link),
+        escape(&feed.title),
+        escape(&feed.description),
+        escape(&feed.link),
+        escape(&feed.self_link),
+        rfc3339(feed.updated),
+    );
+
+    for item in &feed.items {
+        let _ = write!(
+            out,
+            r#"<entry><id>{}</id><title>{}</title><link href="{}"/><published>{}</published><updated>{}</updated><author><name>{}</name></author><summary>{}</summary><content type="html">{}</content>"#,
+            escape(&item.id),
+            escape(&item.title),
+            escape(&item.link),
+            rfc3339(item.published),
+            rfc3339(item.updated),
+            escape(&item.author),
+            escape(&item.summary),
+            escape(&item.html),
+        );
+
+        for category in &item.categories {
+            let _ = write!(out, r#"<category term="{}"/>"#, escape(category));
+        }
+
+        out.push_str("</entry>");
+    }
+
+    out.push_str("</feed>");
+
+    out
+}
+
+/// Origin of the website the request was proxied from. Forwarded headers are already gone when the
+/// request didn't come from a trusted proxy, as the response is cached.
+fn request_origin(headers: &HeaderMap) -> String {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(',').next())
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+    };
+
+    let proto = header("x-forwarded-proto").unwrap_or("https");
+    let host = header("x-forwarded-host")
+        .or_else(|| header("host"))
+        .unwrap_or("localhost");
+
+    format!("{proto}://{host}")
+}
+
+fn http_date(value: OffsetDateTime) -> String {
+    value
+        .to_offset(time::UtcOffset::UTC)
+        .format(HTTP_DATE)
+        .unwrap_or_default()
+}
+
+fn rfc3339(value: OffsetDateTime) -> String {
+    value.format(&Rfc3339).unwrap_or_default()
+}
@@ -0,0 +1,78 @@
+//! Website member signed in on the page making the request
+
+use webby_addon_common::MemberUuid;
+use async_trait::async_trait;
+use axum::{extract::FromRequestParts, http::request::Parts};
+use sqlx::SqliteConnection;
+
+use super::proxy;
+use crate::{
+    models::{AuthorModel, AuthorRole},
+    BlogId, Error, Result,
+};
+
+/// Set by the website for requests from a signed in member. Only read from trusted proxies.
+pub const MEMBER_HEADER: &str = "x-member-id";
+
+/// The signed in member, if any. A malformed header counts as signed out, and so does one which
+/// didn't come through the website as anyone could send it.
+pub struct OptionalMember(pub Option<MemberUuid>);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for OptionalMember {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        if !proxy::is_trusted(&parts.extensions) {
+            return Ok(Self(None));
+        }
+
+        let member = parts
+            .headers
+            .get(MEMBER_HEADER)
+            .and_then(|v| v.to_str().ok())
+            // Parsed the same way as member ids sent in JSON bodies
+            .and_then(|v| serde_json::from_value::<MemberUuid>(v.trim().into()).ok());
+
+        Ok(Self(member))
+    }
+}
+
+/// The signed in member. Used by dashboard routes.
+pub struct Member(pub MemberUuid);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Member {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self> {
+        let OptionalMember(member) = OptionalMember::from_request_parts(parts, state).await?;
+
+        member.map(Self).ok_or(Error::Forbidden("Not signed in"))
+    }
+}
+
+impl Member {
+    /// Author profile of the member on the blog.
+    pub async fn author(&self, blog_id: BlogId, db: &mut SqliteConnection) -> Result<AuthorModel> {
+        AuthorModel::find_one_by_member_id(blog_id, self.0, db)
+            .await?
+            .ok_or(Error::Forbidden("Not an author of this blog"))
+    }
+
+    /// Author profile of the member, as long as their role passes the check.
+    pub async fn author_with(
+        &self,
+        blog_id: BlogId,
+        check: fn(AuthorRole) -> bool,
+        db: &mut SqliteConnection,
+    ) -> Result<AuthorModel> {
+        let author = self.author(blog_id, db).await?;
+
+        if !check(author.role) {
+            return Err(Error::Forbidden("Your role doesn't allow this"));
+        }
+
+        Ok(author)
+    }
+}
@@ -1,6 +1,6 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, time::Duration};
 
-use axum::{Extension, Router};
+use axum::{middleware, Extension, Router};
 use eyre::Result;
 use sqlx::SqlitePool;
 use tokio::net::TcpListener;
@@ -8,9 +8,32 @@ use tower_http::trace::TraceLayer;
 
 use crate::upload::register_b2;
 
+use self::rate_limit::{RateLimitConfig, RateLimiter};
+
+mod author;
 mod blog;
+mod category;
 mod cms;
+mod comment;
+mod feed;
+mod member;
+mod post;
+mod proxy;
+mod rate_limit;
 mod register;
+mod search;
+mod site;
+mod tag;
+#[cfg(test)]
+mod tests;
+mod trash;
+
+/// Comments a single client can submit to one blog per window.
+/// Overridden with `COMMENT_RATE_LIMIT` and `COMMENT_RATE_WINDOW_SECS`.
+const COMMENT_RATE_LIMIT: RateLimitConfig = RateLimitConfig {
+    requests: 5,
+    per: Duration::from_secs(60),
+};
 
 pub async fn serve(pool: SqlitePool) -> Result<()> {
     let port = 5940;
@@ -24,15 +47,50 @@ pub async fn serve(pool: SqlitePool) -> Result<()> {
 
     axum::serve(
         listener,
-        Router::new()
-            .nest("/registration", register::routes())
-            .nest("/blog", blog::routes())
-            .nest("/cms", cms::routes())
+        routes()
             .layer(TraceLayer::new_for_http())
             .layer(Extension(uploader))
-            .with_state(pool),
+            .with_state(pool)
+            .into_make_service_with_connect_info::<SocketAddr>(),
     )
     .await?;
 
     Ok(())
 }
+
+/// Every route, without the layers which need the running server.
+fn routes() -> Router<SqlitePool> {
+    Router::new()
+        .nest("/registration", register::routes())
+        .nest(
+            "/blog",
+            blog::routes()
+                .merge(post::routes())
+                .merge(trash::routes())
+                .merge(author::routes())
+                .merge(category::routes())
+                .merge(tag::routes())
+                .merge(comment::routes())
+                .merge(
+                    comment::public_routes().route_layer(middleware::from_fn_with_state(
+                        RateLimiter::new(RateLimitConfig::from_env("COMMENT", COMMENT_RATE_LIMIT)),
+                        rate_limit::limit,
+                    )),
+                )
+                .merge(feed::routes())
+                .merge(search::routes())
+                .merge(site::routes()),
+        )
+        .nest("/cms", cms::routes())
+}
+
+/// Tells an explicit `null` apart from a missing field for `Option<Option<T>>`.
+///
+/// Use with `#[serde(default, deserialize_with = "super::deserialize_some")]`.
+fn deserialize_some<'de, T, D>(deserializer: D) -> std::result::Result<Option<T>, D::Error>
+where
+    T: serde::Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
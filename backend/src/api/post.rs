@@ -0,0 +1,184 @@
+use webby_addon_common::{
+    AddonInstanceUuid, JsonListResponse, JsonResponse, ListResponse, WrappingResponse,
+};
+use axum::{
+    extract,
+    routing::{get, post},
+    Json, Router,
+};
+use sqlx::{SqliteConnection, SqlitePool};
+
+use super::{blog::check_can_edit_post, member::Member};
+use crate::{
+    delta::{self, Delta},
+    models::{AuthorModel, BlogModel, NewPostRevisionModel, PostModel, PostRevisionModel},
+    Error, PostId, PostRevisionId, Result,
+};
+
+pub fn routes() -> Router<SqlitePool> {
+    Router::new()
+        .route("/:instance/post/:post_id/revisions", get(get_revision_list))
+        .route(
+            "/:instance/post/:post_id/revisions/:revision_id",
+            get(get_revision),
+        )
+        .route(
+            "/:instance/post/:post_id/revisions/:revision_id/diff/:other_id",
+            get(get_revision_diff),
+        )
+        .route(
+            "/:instance/post/:post_id/revisions/:revision_id/restore",
+            post(restore_revision),
+        )
+}
+
+/// The post along with the signed in member's author profile.
+async fn find_post(
+    instance_id: AddonInstanceUuid,
+    post_id: PostId,
+    member: &Member,
+    db: &mut SqliteConnection,
+) -> Result<(AuthorModel, PostModel)> {
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut *db).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    let current = member.author(blog.id, &mut *db).await?;
+
+    let Some(post) = PostModel::find_one_by_id(blog.id, post_id, db).await? else {
+        return Err(Error::NotFound("Post not found"));
+    };
+
+    Ok((current, post))
+}
+
+async fn find_revision(
+    post_id: PostId,
+    revision_id: PostRevisionId,
+    db: &mut SqliteConnection,
+) -> Result<PostRevisionModel> {
+    let Some(revision) = PostRevisionModel::find_one_by_id(post_id, revision_id, db).await? else {
+        return Err(Error::NotFound("Revision not found"));
+    };
+
+    Ok(revision)
+}
+
+async fn get_revision_list(
+    extract::Path((instance_id, post_id)): extract::Path<(AddonInstanceUuid, PostId)>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+) -> Result<JsonListResponse<serde_json::Value>> {
+    let mut acq = db.acquire().await?;
+
+    let (_, post) = find_post(instance_id, post_id, &member, &mut acq).await?;
+
+    let revisions = PostRevisionModel::find_by_post_id(post.id, &mut acq).await?;
+
+    Ok(Json(WrappingResponse::okay(ListResponse::all(
+        revisions
+            .into_iter()
+            .map(|rev| {
+                serde_json::json!({
+                    "id": rev.id,
+                    "title": rev.title,
+                    "restored_from": rev.restored_from,
+                    "created_at": rev.created_at,
+                })
+            })
+            .collect(),
+    ))))
+}
+
+async fn get_revision(
+    extract::Path((instance_id, post_id, revision_id)): extract::Path<(
+        AddonInstanceUuid,
+        PostId,
+        PostRevisionId,
+    )>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+) -> Result<JsonResponse<PostRevisionModel>> {
+    let mut acq = db.acquire().await?;
+
+    let (_, post) = find_post(instance_id, post_id, &member, &mut acq).await?;
+
+    let revision = find_revision(post.id, revision_id, &mut acq).await?;
+
+    Ok(Json(WrappingResponse::okay(revision)))
+}
+
+async fn get_revision_diff(
+    extract::Path((instance_id, post_id, revision_id, other_id)): extract::Path<(
+        AddonInstanceUuid,
+        PostId,
+        PostRevisionId,
+        PostRevisionId,
+    )>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+) -> Result<JsonResponse<serde_json::Value>> {
+    let mut acq = db.acquire().await?;
+
+    let (_, post) = find_post(instance_id, post_id, &member, &mut acq).await?;
+
+    let old = find_revision(post.id, revision_id, &mut acq).await?;
+    let new = find_revision(post.id, other_id, &mut acq).await?;
+
+    let changes = delta::diff::diff(
+        &Delta::from_value(&old.content),
+        &Delta::from_value(&new.content),
+    );
+
+    Ok(Json(WrappingResponse::okay(serde_json::json!({
+        "from": old.id,
+        "to": new.id,
+        "title": (old.title != new.title).then(|| serde_json::json!({
+            "from": old.title,
+            "to": new.title,
+        })),
+        "changes": changes,
+    }))))
+}
+
+async fn restore_revision(
+    extract::Path((instance_id, post_id, revision_id)): extract::Path<(
+        AddonInstanceUuid,
+        PostId,
+        PostRevisionId,
+    )>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+) -> Result<JsonResponse<serde_json::Value>> {
+    let mut tx = db.begin().await?;
+
+    let (current, mut post) = find_post(instance_id, post_id, &member, &mut tx).await?;
+
+    check_can_edit_post(&current, &post, &mut tx).await?;
+
+    let revision = find_revision(post.id, revision_id, &mut tx).await?;
+
+    post.title = revision.title;
+    post.content = revision.content;
+
+    post.update(&mut tx).await?;
+
+    // Restoring never rewrites history - it's recorded as a new revision
+    let new_revision = NewPostRevisionModel {
+        blog_id: post.blog_id,
+        post_id: post.id,
+        title: post.title.clone(),
+        content: post.content.0.clone(),
+        restored_from: Some(revision.id),
+    }
+    .insert(&mut tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(WrappingResponse::okay(serde_json::json!({
+        "id": post.id,
+        "slug": post.slug,
+        "revision": new_revision.id,
+    }))))
+}
@@ -0,0 +1,63 @@
+//! The website's servers in front of the addon
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use async_trait::async_trait;
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::{request::Parts, Extensions, HeaderMap},
+};
+use lazy_static::lazy_static;
+
+use crate::{Error, Result};
+
+/// Set by the proxy to describe the original request
+const FORWARDED_HEADERS: [&str; 5] = [
+    "forwarded",
+    "x-forwarded-for",
+    "x-forwarded-host",
+    "x-forwarded-proto",
+    "x-real-ip",
+];
+
+lazy_static! {
+    /// Proxies in front of the addon, from the comma separated `TRUSTED_PROXIES`. Loopback when unset.
+    ///
+    /// Headers set by the website, like forwarded addresses and the signed in member, are ignored
+    /// from anyone else.
+    pub static ref TRUSTED_PROXIES: Vec<IpAddr> = std::env::var("TRUSTED_PROXIES")
+        .map(|v| v.split(',').filter_map(|v| v.trim().parse().ok()).collect())
+        .unwrap_or_else(|_| vec![Ipv4Addr::LOCALHOST.into(), Ipv6Addr::LOCALHOST.into()]);
+}
+
+/// Address of the connected peer. Unknown when served without connect info.
+pub fn peer_ip(extensions: &Extensions) -> Option<IpAddr> {
+    extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())
+}
+
+/// Whether the request came straight from one of the trusted proxies.
+pub fn is_trusted(extensions: &Extensions) -> bool {
+    peer_ip(extensions).is_some_and(|ip| TRUSTED_PROXIES.contains(&ip))
+}
+
+/// Request headers, without the forwarded ones unless the request came from a trusted proxy.
+pub struct ProxiedHeaders(pub HeaderMap);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ProxiedHeaders {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        let mut headers = parts.headers.clone();
+
+        if !is_trusted(&parts.extensions) {
+            for name in FORWARDED_HEADERS {
+                headers.remove(name);
+            }
+        }
+
+        Ok(Self(headers))
+    }
+}
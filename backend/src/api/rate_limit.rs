@@ -0,0 +1,325 @@
+//! Per client rate limiting for public write endpoints
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{RawPathParams, Request, State},
+    http::{HeaderMap, Method},
+    middleware::Next,
+    response::Response,
+};
+use uuid::Uuid;
+
+use super::proxy::{self, TRUSTED_PROXIES};
+use crate::{Error, Result};
+
+/// Clients tracked by a single limiter. The oldest window is dropped to make room past this.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Limit of a single route group.
+#[derive(Clone, Copy)]
+pub struct RateLimitConfig {
+    pub requests: u32,
+    pub per: Duration,
+}
+
+impl RateLimitConfig {
+    /// Overrides the defaults with `{group}_RATE_LIMIT` and `{group}_RATE_WINDOW_SECS`.
+    pub fn from_env(group: &str, default: Self) -> Self {
+        let var = |name: &str| {
+            std::env::var(format!("{group}_{name}"))
+                .ok()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+        };
+
+        Self {
+            requests: var("RATE_LIMIT")
+                .and_then(|v| u32::try_from(v).ok())
+                .unwrap_or(default.requests),
+            per: var("RATE_WINDOW_SECS")
+                .filter(|v| *v != 0)
+                .map_or(default.per, Duration::from_secs),
+        }
+    }
+}
+
+/// Fixed window limiter keyed by client IP and addon instance.
+///
+/// Every route group gets its own limiter, so their windows are counted separately.
+/// Only write requests (anything but GET, HEAD and OPTIONS) count.
+#[derive(Clone)]
+pub struct RateLimiter {
+    requests: u32,
+    per: Duration,
+
+    windows: Arc<Mutex<Windows>>,
+}
+
+struct Windows {
+    entries: HashMap<(Option<IpAddr>, String), Window>,
+    last_pruned: Instant,
+}
+
+struct Window {
+    started: Instant,
+    count: u32,
+}
+
+impl Windows {
+    fn prune(&mut self, now: Instant, per: Duration) {
+        self.entries
+            .retain(|_, window| now.duration_since(window.started) < per);
+        self.last_pruned = now;
+    }
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            requests: config.requests,
+            per: config.per,
+            windows: Arc::new(Mutex::new(Windows {
+                entries: HashMap::new(),
+                last_pruned: Instant::now(),
+            })),
+        }
+    }
+
+    /// Counts a request, returning the seconds to wait when over the limit.
+    fn hit(&self, ip: Option<IpAddr>, instance: String) -> Result<(), u64> {
+        let now = Instant::now();
+
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+
+        if now.duration_since(windows.last_pruned) >= self.per {
+            windows.prune(now, self.per);
+        }
+
+        let key = (ip, instance);
+
+        if !windows.entries.contains_key(&key) && windows.entries.len() >= MAX_TRACKED_CLIENTS {
+            windows.prune(now, self.per);
+
+            if windows.entries.len() >= MAX_TRACKED_CLIENTS {
+                let oldest = windows
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, window)| window.started)
+                    .map(|(key, _)| key.clone());
+
+                if let Some(oldest) = oldest {
+                    windows.entries.remove(&oldest);
+                }
+            }
+        }
+
+        let window = windows.entries.entry(key).or_insert(Window {
+            started: now,
+            count: 0,
+        });
+
+        if now.duration_since(window.started) >= self.per {
+            window.started = now;
+            window.count = 0;
+        }
+
+        if window.count >= self.requests {
+            let remaining = self.per.saturating_sub(now.duration_since(window.started));
+
+            // Rounded up so retrying right on time doesn't hit the limit again
+            return Err(remaining.as_secs() + u64::from(remaining.subsec_nanos() != 0));
+        }
+
+        window.count += 1;
+
+        Ok(())
+    }
+}
+
+/// Middleware for `axum::middleware::from_fn_with_state`.
+pub async fn limit(
+    State(limiter): State<RateLimiter>,
+    params: RawPathParams,
+    request: Request,
+    next: Next,
+) -> Result<Response> {
+    if matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    ) {
+        return Ok(next.run(request).await);
+    }
+
+    let instance = params
+        .iter()
+        .find(|(name, _)| *name == "instance")
+        .map(|(_, value)| instance_key(value))
+        .unwrap_or_default();
+
+    let peer = proxy::peer_ip(request.extensions());
+    let ip = client_ip(request.headers(), peer, &TRUSTED_PROXIES);
+
+    if let Err(retry_after) = limiter.hit(ip, instance) {
+        return Err(Error::RateLimited(retry_after));
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Same key for every spelling of the instance UUID, so changing its case doesn't reset the limit.
+fn instance_key(value: &str) -> String {
+    match Uuid::parse_str(value) {
+        Ok(uuid) => uuid.hyphenated().to_string(),
+        Err(_) => value.to_ascii_lowercase(),
+    }
+}
+
+/// Address of the client. Forwarded headers only count when the peer is a trusted proxy,
+/// and then only the right-most hop which isn't one, as everything before it is made up by the client.
+fn client_ip(headers: &HeaderMap, peer: Option<IpAddr>, trusted: &[IpAddr]) -> Option<IpAddr> {
+    let peer = peer?;
+
+    if !trusted.contains(&peer) {
+        return Some(peer);
+    }
+
+    let hops = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(str::trim)
+        .collect::<Vec<_>>();
+
+    if hops.is_empty() {
+        return headers
+            .get("x-real-ip")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse().ok())
+            .or(Some(peer));
+    }
+
+    for hop in hops.into_iter().rev() {
+        match hop.parse::<IpAddr>() {
+            Ok(ip) if trusted.contains(&ip) => continue,
+            Ok(ip) => return Some(ip),
+            // Not added by one of our proxies, so it can't be trusted
+            Err(_) => break,
+        }
+    }
+
+    Some(peer)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    const PROXY: [u8; 4] = [10, 0, 0, 1];
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    fn forwarded(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn ignores_forwarded_headers_from_untrusted_peers() {
+        let headers = forwarded("1.1.1.1");
+
+        assert_eq!(
+            client_ip(&headers, Some(ip("2.2.2.2")), &[]),
+            Some(ip("2.2.2.2"))
+        );
+        assert_eq!(
+            client_ip(&headers, Some(ip("2.2.2.2")), &[PROXY.into()]),
+            Some(ip("2.2.2.2"))
+        );
+    }
+
+    #[test]
+    fn uses_right_most_untrusted_hop() {
+        let trusted = [PROXY.into(), ip("10.0.0.2")];
+
+        // The client made up 9.9.9.9, the proxies appended the rest
+        let headers = forwarded("9.9.9.9, 1.1.1.1, 10.0.0.2");
+
+        assert_eq!(
+            client_ip(&headers, Some(PROXY.into()), &trusted),
+            Some(ip("1.1.1.1"))
+        );
+
+        let headers = forwarded("not an ip, 1.1.1.1");
+
+        assert_eq!(
+            client_ip(&headers, Some(PROXY.into()), &trusted),
+            Some(ip("1.1.1.1"))
+        );
+
+        assert_eq!(
+            client_ip(&forwarded("junk"), Some(PROXY.into()), &trusted),
+            Some(PROXY.into())
+        );
+    }
+
+    #[test]
+    fn limits_per_client_and_instance() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            requests: 2,
+            per: Duration::from_secs(60),
+        });
+
+        let client = Some(ip("1.1.1.1"));
+
+        assert!(limiter.hit(client, "a".into()).is_ok());
+        assert!(limiter.hit(client, "a".into()).is_ok());
+        assert!(limiter.hit(client, "a".into()).is_err());
+        assert!(limiter.hit(client, "b".into()).is_ok());
+        assert!(limiter.hit(Some(ip("2.2.2.2")), "a".into()).is_ok());
+    }
+
+    #[test]
+    fn normalizes_instance_keys() {
+        const INSTANCE: &str = "6f1c2a3b-4d5e-4f60-8a7b-9c0d1e2f3a4b";
+
+        for spelling in [
+            INSTANCE,
+            "6F1C2A3B-4D5E-4F60-8A7B-9C0D1E2F3A4B",
+            "6f1c2a3b4d5e4f608a7b9c0d1e2f3a4b",
+            "{6f1c2a3b-4d5e-4f60-8a7b-9c0d1e2f3a4b}",
+            "urn:uuid:6F1C2A3B-4D5E-4F60-8A7B-9C0D1E2F3A4B",
+        ] {
+            assert_eq!(instance_key(spelling), INSTANCE, "{spelling}");
+        }
+
+        assert_eq!(instance_key("Not-A-Uuid"), "not-a-uuid");
+    }
+
+    #[test]
+    fn caps_tracked_clients() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            requests: 1,
+            per: Duration::from_secs(60),
+        });
+
+        for n in 0..MAX_TRACKED_CLIENTS as u32 + 10 {
+            assert!(limiter
+                .hit(Some(IpAddr::from(n.to_be_bytes())), "a".into())
+                .is_ok());
+        }
+
+        let windows = limiter.windows.lock().unwrap();
+        assert_eq!(windows.entries.len(), MAX_TRACKED_CLIENTS);
+    }
+}
@@ -2,7 +2,10 @@ use webby_addon_common::{InstallResponse, JsonResponse, RegisterNewJson, Wrappin
 use axum::{extract, routing::post, Json, Router};
 use sqlx::SqlitePool;
 
-use crate::{models::NewBlogModel, Result};
+use crate::{
+    models::{AuthorRole, NewAuthorModel, NewBlogModel},
+    Result,
+};
 
 pub fn routes() -> Router<SqlitePool> {
     Router::new().route("/", post(post_install))
@@ -12,16 +15,34 @@ async fn post_install(
     extract::State(db): extract::State<SqlitePool>,
     extract::Json(value): extract::Json<RegisterNewJson>,
 ) -> Result<JsonResponse<InstallResponse>> {
-    let mut acq = db.acquire().await?;
+    let mut tx = db.begin().await?;
 
-    let _model = NewBlogModel {
+    let blog = NewBlogModel {
         instance_id: value.instance_id,
         external_website_id: value.website_id,
         external_member_id: value.owner_id,
         name: value.website.name,
     }
-    .insert(&mut acq)
+    .insert(&mut tx)
     .await?;
 
+    // The owner is the first author. Their name isn't known yet, so they start out with the same
+    // placeholder as owners of blogs installed before authors existed, and rename it themselves.
+    NewAuthorModel {
+        blog_id: blog.id,
+        external_member_id: blog.external_member_id,
+        name: String::from("Owner"),
+        slug: String::from("owner"),
+        email: None,
+        bio: None,
+        avatar_url: None,
+        links: Vec::new(),
+        role: AuthorRole::Owner,
+    }
+    .insert(&mut tx)
+    .await?;
+
+    tx.commit().await?;
+
     Ok(Json(WrappingResponse::okay(InstallResponse::Complete)))
 }
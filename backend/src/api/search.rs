@@ -0,0 +1,159 @@
+use webby_addon_common::{
+    AddonInstanceUuid, JsonListResponse, JsonResponse, ListResponse, WrappingResponse,
+};
+use axum::{
+    extract,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use sqlx::SqlitePool;
+
+use super::member::Member;
+use crate::{
+    delta::html::escape,
+    models::{
+        to_match_query, AuthorRole, BlogModel, PostSearchModel, HIGHLIGHT_END, HIGHLIGHT_START,
+    },
+    Error, Result,
+};
+
+const DEFAULT_SEARCH_LIMIT: i64 = 10;
+const MAX_SEARCH_LIMIT: i64 = 50;
+
+pub fn routes() -> Router<SqlitePool> {
+    Router::new()
+        .route("/:instance/search", get(get_search))
+        .route("/:instance/search/reindex", post(post_reindex))
+}
+
+#[derive(Deserialize)]
+struct SearchQuery {
+    q: String,
+    offset: Option<i64>,
+    limit: Option<i64>,
+}
+
+async fn get_search(
+    extract::Path(instance_id): extract::Path<AddonInstanceUuid>,
+    extract::State(db): extract::State<SqlitePool>,
+    extract::Query(SearchQuery { q, offset, limit }): extract::Query<SearchQuery>,
+) -> Result<JsonListResponse<serde_json::Value>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    let offset = offset.unwrap_or(0).max(0);
+    let limit = limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+
+    let Some(query) = to_match_query(&q) else {
+        return Ok(Json(WrappingResponse::okay(ListResponse {
+            offset,
+            limit,
+            total: 0,
+            items: Vec::new(),
+        })));
+    };
+
+    let (results, total) =
+        PostSearchModel::search(blog.id, &query, offset, limit, &mut acq).await?;
+
+    let items = results
+        .into_iter()
+        .map(|result| {
+            serde_json::json!({
+                "id": result.id,
+                "slug": result.slug,
+                "title": result.title,
+                "excerpt": result.custom_excerpt.filter(|v| !v.trim().is_empty()).unwrap_or(result.excerpt),
+                "post_date": result.post_date,
+                "title_html": highlight_html(&result.title_highlight),
+                "snippet_html": highlight_html(&result.snippet),
+                "rank": result.rank,
+            })
+        })
+        .collect();
+
+    Ok(Json(WrappingResponse::okay(ListResponse {
+        offset,
+        limit,
+        total,
+        items,
+    })))
+}
+
+async fn post_reindex(
+    extract::Path(instance_id): extract::Path<AddonInstanceUuid>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+) -> Result<JsonResponse<serde_json::Value>> {
+    let mut tx = db.begin().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut tx).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    member
+        .author_with(blog.id, AuthorRole::can_edit_others, &mut tx)
+        .await?;
+
+    let indexed = PostSearchModel::reindex(blog.id, &mut tx).await?;
+
+    tx.commit().await?;
+
+    Ok(Json(WrappingResponse::okay(serde_json::json!({
+        "indexed": indexed,
+    }))))
+}
+
+/// Escapes the text and turns the search highlight markers into `<mark>` tags.
+///
+/// Markers which would leave the tags unbalanced are dropped, as titles and posts indexed before
+/// their text was cleaned up can contain them.
+fn highlight_html(value: &str) -> String {
+    let mut html = String::with_capacity(value.len());
+    let mut open = false;
+    let mut last = 0;
+
+    for (i, marker) in value.match_indices([HIGHLIGHT_START, HIGHLIGHT_END]) {
+        html.push_str(&escape(&value[last..i]));
+        last = i + marker.len();
+
+        match (marker.starts_with(HIGHLIGHT_START), open) {
+            (true, false) => html.push_str("<mark>"),
+            (false, true) => html.push_str("</mark>"),
+            _ => continue,
+        }
+
+        open = !open;
+    }
+
+    html.push_str(&escape(&value[last..]));
+
+    if open {
+        html.push_str("</mark>");
+    }
+
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highlights_are_balanced() {
+        assert_eq!(
+            highlight_html("a \u{2}<b>\u{3} c"),
+            "a <mark>&lt;b&gt;</mark> c"
+        );
+        assert_eq!(
+            highlight_html("\u{3}a \u{2}\u{2}b\u{3}\u{3} \u{2}c"),
+            "a <mark>b</mark> <mark>c</mark>"
+        );
+    }
+}
@@ -0,0 +1,116 @@
+//! Public, read only routes used by the site renderer
+
+use webby_addon_common::{AddonInstanceUuid, JsonResponse, WrappingResponse};
+use axum::{extract, routing::get, Json, Router};
+use sqlx::{SqliteConnection, SqlitePool};
+
+use super::author::public_author;
+use crate::{
+    delta::{self, Delta},
+    models::{AuthorModel, BlogModel, CategoryModel, PostModel, PostSlugHistoryModel, TagModel},
+    BlogId, Error, Result,
+};
+
+pub fn routes() -> Router<SqlitePool> {
+    Router::new()
+        .route("/:instance/posts/:slug", get(get_public_post))
+        .route("/:instance/resolve/:slug", get(resolve_post_slug))
+}
+
+/// A published post ready to be shown on the site.
+///
+/// Old slugs return the post too, with `moved` set.
+async fn get_public_post(
+    extract::Path((instance_id, slug)): extract::Path<(AddonInstanceUuid, String)>,
+    extract::State(db): extract::State<SqlitePool>,
+) -> Result<JsonResponse<serde_json::Value>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    let Some((post, moved)) = find_public_post_by_slug(blog.id, &slug, &mut acq).await? else {
+        return Err(Error::NotFound("Post not found"));
+    };
+
+    let html = delta::html::render(&Delta::from_value(&post.content));
+
+    let author = match post.author_id {
+        Some(id) => AuthorModel::find_one_by_id(post.blog_id, id, &mut acq).await?,
+        None => None,
+    };
+
+    let co_authors = AuthorModel::find_by_post_id(post.blog_id, post.id, &mut acq).await?;
+    let categories = CategoryModel::find_by_post_id(post.blog_id, post.id, &mut acq).await?;
+    let tags = TagModel::find_by_post_id(post.blog_id, post.id, &mut acq).await?;
+
+    Ok(Json(WrappingResponse::okay(serde_json::json!({
+        "id": post.id,
+        "slug": post.slug,
+        "moved": moved,
+        "title": post.title,
+        "html": html,
+        "excerpt": post.display_excerpt(),
+        "word_count": post.word_count,
+        "read_minutes": post.read_minutes,
+        "author": author.as_ref().map(public_author),
+        "co_authors": co_authors.iter().map(public_author).collect::<Vec<_>>(),
+        "categories": categories
+            .iter()
+            .map(|v| serde_json::json!({ "name": v.name, "slug": v.slug }))
+            .collect::<Vec<_>>(),
+        "tags": tags
+            .iter()
+            .map(|v| serde_json::json!({ "name": v.name, "slug": v.slug }))
+            .collect::<Vec<_>>(),
+        "post_date": post.post_date,
+        "updated_at": post.updated_at,
+    }))))
+}
+
+/// Looks up which post a slug points to.
+///
+/// Old slugs resolve to the post's current slug with `moved` set, so the site can redirect with a 301.
+async fn resolve_post_slug(
+    extract::Path((instance_id, slug)): extract::Path<(AddonInstanceUuid, String)>,
+    extract::State(db): extract::State<SqlitePool>,
+) -> Result<JsonResponse<serde_json::Value>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    let Some((post, moved)) = find_public_post_by_slug(blog.id, &slug, &mut acq).await? else {
+        return Err(Error::NotFound("Post not found"));
+    };
+
+    Ok(Json(WrappingResponse::okay(serde_json::json!({
+        "id": post.id,
+        "slug": post.slug,
+        "title": post.title,
+        "moved": moved,
+    }))))
+}
+
+/// Finds a published post by its current or a previous slug. The flag is set for previous slugs.
+async fn find_public_post_by_slug(
+    blog_id: BlogId,
+    slug: &str,
+    db: &mut SqliteConnection,
+) -> Result<Option<(PostModel, bool)>> {
+    if let Some(post) = PostModel::find_one_by_slug(blog_id, slug, db).await? {
+        return Ok(Some((post, false)).filter(|(post, _)| post.is_public()));
+    }
+
+    let Some(old) = PostSlugHistoryModel::find_one_by_slug(blog_id, slug, db).await? else {
+        return Ok(None);
+    };
+
+    let post = PostModel::find_one_by_id(blog_id, old.post_id, db)
+        .await?
+        .filter(|v| v.is_public());
+
+    Ok(post.map(|v| (v, true)))
+}
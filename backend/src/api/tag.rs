@@ -0,0 +1,260 @@
+use webby_addon_common::{
+    AddonInstanceUuid, JsonListResponse, JsonResponse, ListResponse, WrappingResponse,
+};
+use axum::{
+    extract,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use sqlx::{SqliteConnection, SqlitePool};
+
+use super::member::Member;
+use crate::{
+    models::{slugify, AuthorRole, BlogModel, NewTagModel, TagModel, TagUsageModel},
+    BlogId, Error, Result, TagId,
+};
+
+const DEFAULT_AUTOCOMPLETE_LIMIT: i64 = 10;
+const MAX_AUTOCOMPLETE_LIMIT: i64 = 50;
+
+pub fn routes() -> Router<SqlitePool> {
+    Router::new()
+        .route("/:instance/tags", get(get_tag_list))
+        .route("/:instance/tags/autocomplete", get(get_tag_autocomplete))
+        .route("/:instance/tag", post(create_tag))
+        .route(
+            "/:instance/tag/:tag_id",
+            get(get_tag).post(rename_tag).delete(delete_tag),
+        )
+        .route("/:instance/tag/:tag_id/merge", post(merge_tag))
+}
+
+async fn get_tag_list(
+    extract::Path(instance_id): extract::Path<AddonInstanceUuid>,
+    extract::State(db): extract::State<SqlitePool>,
+) -> Result<JsonListResponse<TagUsageModel>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    let tags = TagModel::find_by_blog_id_with_usage(blog.id, &mut acq).await?;
+
+    Ok(Json(WrappingResponse::okay(ListResponse::all(tags))))
+}
+
+#[derive(Deserialize)]
+struct AutocompleteQuery {
+    q: String,
+    limit: Option<i64>,
+}
+
+async fn get_tag_autocomplete(
+    extract::Path(instance_id): extract::Path<AddonInstanceUuid>,
+    extract::State(db): extract::State<SqlitePool>,
+    extract::Query(AutocompleteQuery { q, limit }): extract::Query<AutocompleteQuery>,
+) -> Result<JsonListResponse<TagUsageModel>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    let limit = limit
+        .unwrap_or(DEFAULT_AUTOCOMPLETE_LIMIT)
+        .clamp(1, MAX_AUTOCOMPLETE_LIMIT);
+
+    let tags = TagModel::autocomplete(blog.id, q.trim(), limit, &mut acq).await?;
+
+    Ok(Json(WrappingResponse::okay(ListResponse::all(tags))))
+}
+
+#[derive(Deserialize)]
+struct TagJson {
+    name: String,
+    slug: Option<String>,
+}
+
+async fn create_tag(
+    extract::Path(instance_id): extract::Path<AddonInstanceUuid>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+    extract::Json(TagJson { name, slug }): extract::Json<TagJson>,
+) -> Result<JsonResponse<TagModel>> {
+    let mut tx = db.begin().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut tx).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    member.author(blog.id, &mut tx).await?;
+
+    let name = name.trim().to_string();
+
+    if name.is_empty() {
+        return Err(eyre::eyre!("Tag name is required"))?;
+    }
+
+    let slug = tag_slug(blog.id, slug.as_deref().unwrap_or(&name), None, &mut tx).await?;
+
+    let tag = NewTagModel {
+        blog_id: blog.id,
+        name,
+        slug,
+    }
+    .insert(&mut tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(WrappingResponse::okay(tag)))
+}
+
+async fn get_tag(
+    extract::Path((instance_id, tag_id)): extract::Path<(AddonInstanceUuid, TagId)>,
+    extract::State(db): extract::State<SqlitePool>,
+) -> Result<JsonResponse<TagModel>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    let Some(tag) = TagModel::find_one_by_id(blog.id, tag_id, &mut acq).await? else {
+        return Err(Error::NotFound("Tag not found"));
+    };
+
+    Ok(Json(WrappingResponse::okay(tag)))
+}
+
+/// The previous slug keeps resolving to the tag.
+async fn rename_tag(
+    extract::Path((instance_id, tag_id)): extract::Path<(AddonInstanceUuid, TagId)>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+    extract::Json(TagJson { name, slug }): extract::Json<TagJson>,
+) -> Result<JsonResponse<TagModel>> {
+    let mut tx = db.begin().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut tx).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    member
+        .author_with(blog.id, AuthorRole::can_edit_others, &mut tx)
+        .await?;
+
+    let Some(mut tag) = TagModel::find_one_by_id(blog.id, tag_id, &mut tx).await? else {
+        return Err(Error::NotFound("Tag not found"));
+    };
+
+    let name = name.trim().to_string();
+
+    if name.is_empty() {
+        return Err(eyre::eyre!("Tag name is required"))?;
+    }
+
+    let slug = tag_slug(
+        blog.id,
+        slug.as_deref().unwrap_or(&name),
+        Some(tag.id),
+        &mut tx,
+    )
+    .await?;
+
+    tag.rename(name, slug, &mut tx).await?;
+
+    tx.commit().await?;
+
+    Ok(Json(WrappingResponse::okay(tag)))
+}
+
+#[derive(Deserialize)]
+struct MergeTagJson {
+    into: TagId,
+}
+
+/// Moves every post of the tag onto another one and deletes it.
+async fn merge_tag(
+    extract::Path((instance_id, tag_id)): extract::Path<(AddonInstanceUuid, TagId)>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+    extract::Json(MergeTagJson { into }): extract::Json<MergeTagJson>,
+) -> Result<JsonResponse<TagModel>> {
+    let mut tx = db.begin().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut tx).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    member
+        .author_with(blog.id, AuthorRole::can_edit_others, &mut tx)
+        .await?;
+
+    if tag_id == into {
+        return Err(eyre::eyre!("Tag can't be merged into itself"))?;
+    }
+
+    if TagModel::find_one_by_id(blog.id, tag_id, &mut tx)
+        .await?
+        .is_none()
+    {
+        return Err(Error::NotFound("Tag not found"));
+    }
+
+    let Some(target) = TagModel::find_one_by_id(blog.id, into, &mut tx).await? else {
+        return Err(Error::NotFound("Tag not found"));
+    };
+
+    TagModel::merge(blog.id, tag_id, target.id, &mut tx).await?;
+
+    tx.commit().await?;
+
+    Ok(Json(WrappingResponse::okay(target)))
+}
+
+async fn delete_tag(
+    extract::Path((instance_id, tag_id)): extract::Path<(AddonInstanceUuid, TagId)>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+) -> Result<JsonResponse<&'static str>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    member
+        .author_with(blog.id, AuthorRole::can_edit_others, &mut acq)
+        .await?;
+
+    if TagModel::delete(blog.id, tag_id, &mut acq).await? == 0 {
+        return Err(Error::NotFound("Tag not found"));
+    }
+
+    Ok(Json(WrappingResponse::okay("success")))
+}
+
+/// Slugifies the value, making sure no other tag of the blog uses it.
+async fn tag_slug(
+    blog_id: BlogId,
+    value: &str,
+    current: Option<TagId>,
+    db: &mut SqliteConnection,
+) -> Result<String> {
+    let slug = slugify(value);
+
+    if slug.is_empty() {
+        return Err(eyre::eyre!("Tag slug is empty"))?;
+    }
+
+    if let Some(found) = TagModel::find_one_by_slug(blog_id, &slug, db).await? {
+        if Some(found.id) != current {
+            return Err(eyre::eyre!("Tag slug is already in use"))?;
+        }
+    }
+
+    Ok(slug)
+}
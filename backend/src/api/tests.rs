@@ -0,0 +1,795 @@
+//! Requests against the full router, with two blogs sharing an in-memory database
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::ConnectInfo,
+    http::{Method, Request, StatusCode},
+    Router,
+};
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+use time::{Duration, OffsetDateTime};
+use tower::ServiceExt;
+
+use super::{member::MEMBER_HEADER, routes};
+use crate::{
+    models::{
+        AuthorRole, CommentStatus, NewAuthorModel, NewBlogModel, NewCommentModel, MAX_SLUG_LENGTH,
+    },
+    spam, BlogId, PostId,
+};
+
+/// Instance and owner uuid of each blog
+const BLOG_A: &str = "00000000-0000-0000-0000-00000000000a";
+const BLOG_B: &str = "00000000-0000-0000-0000-00000000000b";
+
+struct TestApp {
+    pool: SqlitePool,
+    router: Router,
+}
+
+impl TestApp {
+    async fn new() -> Self {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        let mut acq = pool.acquire().await.unwrap();
+
+        for uuid in [BLOG_A, BLOG_B] {
+            let blog = NewBlogModel {
+                instance_id: serde_json::from_value(json!(uuid)).unwrap(),
+                external_website_id: serde_json::from_value(json!(uuid)).unwrap(),
+                external_member_id: serde_json::from_value(json!(uuid)).unwrap(),
+                name: format!("Blog {uuid}"),
+            }
+            .insert(&mut acq)
+            .await
+            .unwrap();
+
+            NewAuthorModel {
+                blog_id: blog.id,
+                external_member_id: blog.external_member_id,
+                name: String::from("Owner"),
+                slug: String::from("owner"),
+                email: None,
+                bio: None,
+                avatar_url: None,
+                links: Vec::new(),
+                role: AuthorRole::Owner,
+            }
+            .insert(&mut acq)
+            .await
+            .unwrap();
+        }
+
+        drop(acq);
+
+        Self {
+            router: routes().with_state(pool.clone()),
+            pool,
+        }
+    }
+
+    /// Sends the request as the owner of `blog`.
+    async fn request(
+        &self,
+        method: Method,
+        blog: &str,
+        path: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, String) {
+        self.request_as(Some(blog), method, &format!("/blog/{blog}{path}"), body)
+            .await
+    }
+
+    /// Sends the request through the website, which is on loopback.
+    async fn request_as(
+        &self,
+        member: Option<&str>,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, String) {
+        self.request_from(Ipv4Addr::LOCALHOST.into(), member, method, uri, body)
+            .await
+    }
+
+    async fn request_from(
+        &self,
+        peer: IpAddr,
+        member: Option<&str>,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, String) {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .extension(ConnectInfo(SocketAddr::new(peer, 40000)));
+
+        if let Some(member) = member {
+            request = request.header(MEMBER_HEADER, member);
+        }
+
+        let request = request
+            .body(body.map_or_else(Body::empty, |v| Body::from(v.to_string())))
+            .unwrap();
+
+        self.send(request).await
+    }
+
+    async fn send(&self, request: Request<Body>) -> (StatusCode, String) {
+        let response = self.router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    async fn ok(&self, method: Method, path: &str, body: Option<Value>) -> Value {
+        let (status, body) = self.request(method, BLOG_A, path, body).await;
+        assert_eq!(status, StatusCode::OK, "{path}: {body}");
+
+        serde_json::from_str(&body).unwrap()
+    }
+
+    /// Blog B tries to reach something of blog A.
+    async fn assert_not_found(&self, method: Method, path: &str, body: Option<Value>) {
+        let (status, body) = self.request(method.clone(), BLOG_B, path, body).await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND, "{method} {path}: {body}");
+    }
+
+    async fn create_post(&self, title: &str) -> Value {
+        self.ok(
+            Method::POST,
+            "/post",
+            Some(json!({
+                "title": title,
+                "status": "Published",
+                "post_date": "2020-01-01T00:00:00Z",
+                "content": { "ops": [{ "insert": "Hello\n" }] },
+            })),
+        )
+        .await
+    }
+
+    async fn count(&self, sql: &str) -> i64 {
+        sqlx::query_scalar(sql).fetch_one(&self.pool).await.unwrap()
+    }
+}
+
+#[tokio::test]
+async fn posts_are_scoped_to_their_blog() {
+    let app = TestApp::new().await;
+
+    app.create_post("Private").await;
+
+    app.assert_not_found(Method::GET, "/post/1", None).await;
+    app.assert_not_found(Method::POST, "/post/1", Some(json!({ "title": "Taken" })))
+        .await;
+    app.assert_not_found(Method::DELETE, "/post/1", None).await;
+    app.assert_not_found(Method::GET, "/post/1/revisions", None)
+        .await;
+    app.assert_not_found(Method::POST, "/post/1/revisions/1/restore", None)
+        .await;
+    app.assert_not_found(Method::GET, "/posts/private", None)
+        .await;
+
+    let post = app.ok(Method::GET, "/post/1", None).await;
+    assert_eq!(post["value"]["title"], "Private");
+    assert_eq!(app.count("SELECT COUNT(*) FROM post_revision").await, 1);
+    assert_eq!(
+        app.count("SELECT COUNT(*) FROM post WHERE deleted_at IS NULL")
+            .await,
+        1
+    );
+}
+
+#[tokio::test]
+async fn trash_is_scoped_to_its_blog() {
+    let app = TestApp::new().await;
+
+    app.create_post("Trashed").await;
+    app.ok(Method::DELETE, "/post/1", None).await;
+
+    app.assert_not_found(Method::POST, "/trash/1/restore", None)
+        .await;
+    app.assert_not_found(Method::DELETE, "/trash/1", None).await;
+
+    let (status, body) = app.request(Method::GET, BLOG_B, "/trash", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(!body.contains("Trashed"), "{body}");
+
+    assert_eq!(
+        app.count("SELECT COUNT(*) FROM post WHERE deleted_at IS NOT NULL")
+            .await,
+        1
+    );
+}
+
+#[tokio::test]
+async fn comments_are_scoped_to_their_blog() {
+    let app = TestApp::new().await;
+
+    app.create_post("Commented").await;
+
+    NewCommentModel {
+        blog_id: BlogId::from(1),
+        post_id: PostId::from(1),
+        parent_id: None,
+        depth: 0,
+        external_member_id: None,
+        author_name: String::from("Reader"),
+        email: None,
+        comment: String::from("Nice post"),
+        status: CommentStatus::Pending,
+        spam_score: 0.0,
+        spam_reasons: Vec::new(),
+    }
+    .insert(&mut app.pool.acquire().await.unwrap())
+    .await
+    .unwrap();
+
+    app.assert_not_found(Method::GET, "/comment/1", None).await;
+    app.assert_not_found(
+        Method::POST,
+        "/comment/1",
+        Some(json!({ "comment": "Edited" })),
+    )
+    .await;
+    app.assert_not_found(Method::POST, "/comment/1/approve", None)
+        .await;
+    app.assert_not_found(Method::POST, "/comment/1/deny", None)
+        .await;
+    app.assert_not_found(Method::DELETE, "/comment/1", None)
+        .await;
+
+    let comment = app.ok(Method::GET, "/comment/1", None).await;
+    assert_eq!(comment["value"]["comment"], "Nice post");
+    assert_eq!(comment["value"]["status"], json!(CommentStatus::Pending));
+    assert!(comment["value"]["deleted_at"].is_null());
+}
+
+#[tokio::test]
+async fn edited_comments_are_checked_again() {
+    const READER: &str = "00000000-0000-0000-0000-0000000000d0";
+
+    let app = TestApp::new().await;
+
+    app.create_post("Commented").await;
+    app.ok(
+        Method::POST,
+        "/comments/blocklist",
+        Some(json!({ "words": ["casino"] })),
+    )
+    .await;
+
+    for _ in 0..2 {
+        NewCommentModel {
+            blog_id: BlogId::from(1),
+            post_id: PostId::from(1),
+            parent_id: None,
+            depth: 0,
+            external_member_id: Some(serde_json::from_value(json!(READER)).unwrap()),
+            author_name: String::from("Reader"),
+            email: None,
+            comment: String::from("Nice post"),
+            status: CommentStatus::Approved,
+            spam_score: 0.0,
+            spam_reasons: Vec::new(),
+        }
+        .insert(&mut app.pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    }
+
+    for (id, text) in [(1, "Nice post, fixed a typo"), (2, "Visit my casino")] {
+        let (status, body) = app
+            .request_as(
+                Some(READER),
+                Method::POST,
+                &format!("/blog/{BLOG_A}/post/1/comment/{id}"),
+                Some(json!({ "comment": text })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+    }
+
+    assert_eq!(
+        app.count("SELECT status FROM comment WHERE id = 1").await,
+        CommentStatus::Approved as i64
+    );
+    assert_eq!(
+        app.count("SELECT status FROM comment WHERE id = 2").await,
+        CommentStatus::Spam as i64
+    );
+}
+
+#[tokio::test]
+async fn members_are_only_auto_approved_without_spam_signals() {
+    const READER: &str = "00000000-0000-0000-0000-0000000000d0";
+
+    let app = TestApp::new().await;
+
+    app.create_post("Commented").await;
+    app.ok(
+        Method::POST,
+        "/comments/settings",
+        Some(json!({ "auto_approve_members": true })),
+    )
+    .await;
+
+    let token = spam::token::issue(
+        PostId::from(1),
+        OffsetDateTime::now_utc() - Duration::seconds(30),
+    );
+
+    for (token, status) in [
+        (Some(token), CommentStatus::Approved),
+        (None, CommentStatus::Pending),
+    ] {
+        let (code, body) = app
+            .request_as(
+                Some(READER),
+                Method::POST,
+                &format!("/blog/{BLOG_A}/post/1/comments"),
+                Some(json!({ "author_name": "Reader", "comment": "Nice post", "token": token })),
+            )
+            .await;
+        assert_eq!(code, StatusCode::OK, "{body}");
+
+        assert_eq!(
+            app.count("SELECT status FROM comment ORDER BY id DESC LIMIT 1")
+                .await,
+            status as i64
+        );
+    }
+}
+
+#[tokio::test]
+async fn edited_comments_are_untrained() {
+    const READER: &str = "00000000-0000-0000-0000-0000000000d0";
+
+    let app = TestApp::new().await;
+
+    app.create_post("Commented").await;
+
+    for _ in 0..2 {
+        NewCommentModel {
+            blog_id: BlogId::from(1),
+            post_id: PostId::from(1),
+            parent_id: None,
+            depth: 0,
+            external_member_id: Some(serde_json::from_value(json!(READER)).unwrap()),
+            author_name: String::from("Reader"),
+            email: None,
+            comment: String::from("Cheap pills here"),
+            status: CommentStatus::Pending,
+            spam_score: 0.0,
+            spam_reasons: Vec::new(),
+        }
+        .insert(&mut app.pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    }
+
+    app.ok(Method::POST, "/comment/1/deny", None).await;
+    app.ok(Method::POST, "/comment/2/approve", None).await;
+
+    assert!(
+        app.count("SELECT COUNT(*) FROM spam_token WHERE spam_count > 0")
+            .await
+            > 0
+    );
+    assert!(
+        app.count("SELECT COUNT(*) FROM spam_token WHERE ham_count > 0")
+            .await
+            > 0
+    );
+
+    app.ok(
+        Method::POST,
+        "/comment/1",
+        Some(json!({ "comment": "Fixed by a moderator" })),
+    )
+    .await;
+
+    let (status, body) = app
+        .request_as(
+            Some(READER),
+            Method::POST,
+            &format!("/blog/{BLOG_A}/post/1/comment/2"),
+            Some(json!({ "comment": "Fixed by the reader" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    assert_eq!(
+        app.count("SELECT COALESCE(SUM(spam_count + ham_count), 0) FROM spam_token")
+            .await,
+        0
+    );
+    assert_eq!(
+        app.count("SELECT spam_count + ham_count FROM spam_corpus")
+            .await,
+        0
+    );
+    assert_eq!(
+        app.count("SELECT COUNT(*) FROM comment WHERE trained_spam IS NOT NULL")
+            .await,
+        0
+    );
+}
+
+#[tokio::test]
+async fn terms_are_scoped_to_their_blog() {
+    let app = TestApp::new().await;
+
+    app.ok(Method::POST, "/tag", Some(json!({ "name": "Rust" })))
+        .await;
+    app.ok(Method::POST, "/tag", Some(json!({ "name": "Go" })))
+        .await;
+    app.ok(Method::POST, "/category", Some(json!({ "name": "News" })))
+        .await;
+
+    app.assert_not_found(Method::POST, "/tag/1", Some(json!({ "name": "Taken" })))
+        .await;
+    app.assert_not_found(Method::POST, "/tag/1/merge", Some(json!({ "into": 2 })))
+        .await;
+    app.assert_not_found(Method::DELETE, "/tag/1", None).await;
+    app.assert_not_found(
+        Method::POST,
+        "/category/1",
+        Some(json!({ "name": "Taken" })),
+    )
+    .await;
+    app.assert_not_found(Method::DELETE, "/category/1", None)
+        .await;
+
+    // Terms of another blog can't be attached to a post either
+    app.assert_not_found(
+        Method::POST,
+        "/post",
+        Some(json!({ "title": "Mine", "content": { "ops": [] }, "categories": [1] })),
+    )
+    .await;
+    app.assert_not_found(
+        Method::POST,
+        "/post",
+        Some(json!({ "title": "Mine", "content": { "ops": [] }, "tags": [1] })),
+    )
+    .await;
+
+    assert_eq!(
+        app.count("SELECT COUNT(*) FROM tag WHERE name IN ('Rust', 'Go')")
+            .await,
+        2
+    );
+    assert_eq!(
+        app.count("SELECT COUNT(*) FROM category WHERE name = 'News'")
+            .await,
+        1
+    );
+}
+
+#[tokio::test]
+async fn categories_are_attached_by_name_or_slug() {
+    let app = TestApp::new().await;
+
+    app.ok(
+        Method::POST,
+        "/category",
+        Some(json!({ "name": "C++", "slug": "cpp" })),
+    )
+    .await;
+    app.ok(
+        Method::POST,
+        "/category",
+        Some(json!({ "name": "Release Notes" })),
+    )
+    .await;
+
+    app.ok(
+        Method::POST,
+        "/post",
+        Some(json!({
+            "title": "Both",
+            "content": { "ops": [] },
+            "categories": ["c++", "release-notes"],
+        })),
+    )
+    .await;
+
+    assert_eq!(app.count("SELECT COUNT(*) FROM post_category").await, 2);
+}
+
+#[tokio::test]
+async fn post_slugs_get_a_suffix_when_taken() {
+    let app = TestApp::new().await;
+
+    let slug = |post: &Value| post["value"]["slug"].as_str().unwrap().to_string();
+
+    assert_eq!(slug(&app.create_post("Hello").await), "hello");
+    assert_eq!(slug(&app.create_post("Hello").await), "hello-2");
+    assert_eq!(slug(&app.create_post("HELLO").await), "hello-3");
+
+    // Previous slugs stay reserved for their redirect
+    app.ok(Method::POST, "/post/1", Some(json!({ "slug": "renamed" })))
+        .await;
+    assert_eq!(slug(&app.create_post("Hello").await), "hello-4");
+
+    // Suffixes fit within the length limit
+    let title = "word ".repeat(20);
+    let full = slug(&app.create_post(&title).await);
+    let suffixed = slug(&app.create_post(&title).await);
+
+    assert!(suffixed.len() <= MAX_SLUG_LENGTH, "{suffixed}");
+    assert!(suffixed.ends_with("-2"), "{suffixed}");
+    assert_ne!(full, suffixed);
+
+    for _ in 0..9 {
+        app.create_post(&title).await;
+    }
+
+    let suffixed = slug(&app.create_post(&title).await);
+    assert!(suffixed.len() <= MAX_SLUG_LENGTH, "{suffixed}");
+    assert!(suffixed.ends_with("-12"), "{suffixed}");
+
+    // Slugs are per blog
+    let (status, body) = app
+        .request(
+            Method::POST,
+            BLOG_B,
+            "/post",
+            Some(json!({ "title": "Hello", "content": { "ops": [] } })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(slug(&serde_json::from_str(&body).unwrap()), "hello");
+}
+
+#[tokio::test]
+async fn picked_post_slugs_are_checked() {
+    let app = TestApp::new().await;
+
+    app.create_post("Taken").await;
+    app.create_post("Other").await;
+
+    for (slug, expected) in [
+        ("Not A Slug", StatusCode::BAD_REQUEST),
+        (&"long-".repeat(20), StatusCode::BAD_REQUEST),
+        ("taken", StatusCode::CONFLICT),
+        ("free", StatusCode::OK),
+    ] {
+        let (status, body) = app
+            .request(
+                Method::POST,
+                BLOG_A,
+                "/post/2",
+                Some(json!({ "slug": slug })),
+            )
+            .await;
+        assert_eq!(status, expected, "{slug}: {body}");
+
+        if slug.starts_with("long") {
+            assert!(body.contains("too long"), "{body}");
+        }
+    }
+}
+
+#[tokio::test]
+async fn authors_are_scoped_to_their_blog() {
+    let app = TestApp::new().await;
+
+    // Author 1 is the owner of blog A
+    app.assert_not_found(Method::GET, "/author/1", None).await;
+    app.assert_not_found(Method::POST, "/author/1", Some(json!({ "name": "Taken" })))
+        .await;
+
+    let author = app.ok(Method::GET, "/author/1", None).await;
+    assert_eq!(author["value"]["name"], "Owner");
+}
+
+#[tokio::test]
+async fn owner_can_rename_the_placeholder() {
+    let app = TestApp::new().await;
+
+    let author = app
+        .ok(
+            Method::POST,
+            "/author/1",
+            Some(json!({ "name": "Jane Doe", "slug": "jane" })),
+        )
+        .await;
+
+    assert_eq!(author["value"]["name"], "Jane Doe");
+    assert_eq!(author["value"]["slug"], "jane");
+    assert_eq!(author["value"]["role"], "Owner");
+}
+
+#[tokio::test]
+async fn revisions_and_terms_need_a_role() {
+    let app = TestApp::new().await;
+    let contributor = "00000000-0000-0000-0000-0000000000c0";
+
+    NewAuthorModel {
+        blog_id: BlogId::from(1),
+        external_member_id: serde_json::from_value(json!(contributor)).unwrap(),
+        name: String::from("Contributor"),
+        slug: String::from("contributor"),
+        email: None,
+        bio: None,
+        avatar_url: None,
+        links: Vec::new(),
+        role: AuthorRole::Contributor,
+    }
+    .insert(&mut app.pool.acquire().await.unwrap())
+    .await
+    .unwrap();
+
+    app.create_post("Published").await;
+    app.ok(Method::POST, "/tag", Some(json!({ "name": "Rust" })))
+        .await;
+    app.ok(Method::POST, "/category", Some(json!({ "name": "News" })))
+        .await;
+
+    let cases = [
+        (Method::GET, "/post/1/revisions", None),
+        (Method::POST, "/post/1/revisions/1/restore", None),
+        (Method::POST, "/tag", Some(json!({ "name": "Go" }))),
+        (Method::POST, "/category", Some(json!({ "name": "Sport" }))),
+        (Method::POST, "/search/reindex", None),
+    ];
+
+    for (method, path, body) in cases {
+        let (status, body) = app
+            .request_as(None, method, &format!("/blog/{BLOG_A}{path}"), body)
+            .await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "{path}: {body}");
+    }
+
+    let cases = [
+        (Method::POST, "/post/1/revisions/1/restore", None),
+        (Method::POST, "/tag/1", Some(json!({ "name": "Renamed" }))),
+        (Method::POST, "/tag/1/merge", Some(json!({ "into": 1 }))),
+        (Method::DELETE, "/tag/1", None),
+        (
+            Method::POST,
+            "/category/1",
+            Some(json!({ "name": "Renamed" })),
+        ),
+        (Method::DELETE, "/category/1", None),
+        (Method::POST, "/search/reindex", None),
+    ];
+
+    for (method, path, body) in cases {
+        let (status, body) = app
+            .request_as(
+                Some(contributor),
+                method,
+                &format!("/blog/{BLOG_A}{path}"),
+                body,
+            )
+            .await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "{path}: {body}");
+    }
+
+    assert_eq!(app.count("SELECT COUNT(*) FROM post_revision").await, 1);
+    assert_eq!(app.count("SELECT COUNT(*) FROM tag").await, 1);
+    assert_eq!(app.count("SELECT COUNT(*) FROM category").await, 1);
+}
+
+#[tokio::test]
+async fn cms_query_only_lists_published_posts() {
+    let app = TestApp::new().await;
+
+    app.create_post("Published").await;
+    app.ok(
+        Method::POST,
+        "/post",
+        Some(json!({ "title": "Draft", "content": { "ops": [] } })),
+    )
+    .await;
+
+    for query in ["", "?status=Draft", "?status=0"] {
+        let (status, body) = app
+            .request_as(
+                None,
+                Method::GET,
+                &format!("/cms/{BLOG_A}/query{query}"),
+                None,
+            )
+            .await;
+
+        assert_eq!(status, StatusCode::OK, "{query}: {body}");
+        assert!(body.contains("Published"), "{query}: {body}");
+        assert!(!body.contains("Draft"), "{query}: {body}");
+    }
+}
+
+#[tokio::test]
+async fn cms_query_dates_are_compared_in_utc() {
+    let app = TestApp::new().await;
+
+    // Posted at 2020-01-01T00:00:00Z
+    app.create_post("Dated").await;
+
+    for (query, listed) in [
+        ("from=2020-01-01T01:30:00%2B02:00", true),
+        ("from=2020-01-01T00:30:00-01:00", false),
+        ("to=2019-12-31T23:00:00-02:00", true),
+        ("to=2020-01-01T00:30:00%2B01:00", false),
+    ] {
+        let (status, body) = app
+            .request_as(
+                None,
+                Method::GET,
+                &format!("/cms/{BLOG_A}/query?{query}"),
+                None,
+            )
+            .await;
+
+        assert_eq!(status, StatusCode::OK, "{query}: {body}");
+        assert_eq!(body.contains("Dated"), listed, "{query}: {body}");
+    }
+}
+
+#[tokio::test]
+async fn member_header_is_only_trusted_from_the_website() {
+    let app = TestApp::new().await;
+
+    let peer = IpAddr::from([203, 0, 113, 7]);
+    let uri = format!("/blog/{BLOG_A}/tag");
+
+    let (status, body) = app
+        .request_from(
+            peer,
+            Some(BLOG_A),
+            Method::POST,
+            &uri,
+            Some(json!({ "name": "Spoofed" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN, "{body}");
+
+    let (status, body) = app
+        .request_from(
+            peer,
+            Some(BLOG_A),
+            Method::GET,
+            &format!("/blog/{BLOG_A}/comments"),
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN, "{body}");
+
+    assert_eq!(app.count("SELECT COUNT(*) FROM tag").await, 0);
+}
+
+#[tokio::test]
+async fn feed_links_only_follow_forwarded_hosts_from_the_website() {
+    let app = TestApp::new().await;
+
+    app.create_post("Syndicated").await;
+
+    for (peer, origin) in [
+        (IpAddr::from(Ipv4Addr::LOCALHOST), "https://blog.example"),
+        (IpAddr::from([203, 0, 113, 7]), "https://addon.internal"),
+    ] {
+        let request = Request::get(format!("/blog/{BLOG_A}/feed.rss"))
+            .header("host", "addon.internal")
+            .header("x-forwarded-host", "blog.example")
+            .extension(ConnectInfo(SocketAddr::new(peer, 40000)))
+            .body(Body::empty())
+            .unwrap();
+
+        let (status, body) = app.send(request).await;
+
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert!(body.contains(&format!("<link>{origin}</link>")), "{body}");
+    }
+}
@@ -0,0 +1,141 @@
+//! Deleted posts waiting to be purged
+
+use webby_addon_common::{
+    AddonInstanceUuid, JsonListResponse, JsonResponse, ListResponse, WrappingResponse,
+};
+use axum::{
+    extract,
+    routing::{delete, get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use sqlx::{SqliteConnection, SqlitePool};
+
+use super::{blog::check_can_edit_post, member::Member};
+use crate::{
+    models::{AuthorRole, BlogModel, PostModel},
+    scheduler::TRASH_RETENTION,
+    BlogId, Error, PostId, Result,
+};
+
+const DEFAULT_TRASH_LIMIT: i64 = 25;
+const MAX_TRASH_LIMIT: i64 = 100;
+
+pub fn routes() -> Router<SqlitePool> {
+    Router::new()
+        .route("/:instance/trash", get(get_trash_list))
+        .route("/:instance/trash/:post_id", delete(purge_post))
+        .route("/:instance/trash/:post_id/restore", post(restore_post))
+}
+
+async fn find_deleted_post(
+    blog_id: BlogId,
+    post_id: PostId,
+    db: &mut SqliteConnection,
+) -> Result<PostModel> {
+    let Some(post) = PostModel::find_one_deleted_by_id(blog_id, post_id, db).await? else {
+        return Err(Error::NotFound("Post not found in the trash"));
+    };
+
+    Ok(post)
+}
+
+#[derive(Deserialize)]
+struct TrashQuery {
+    offset: Option<i64>,
+    limit: Option<i64>,
+}
+
+async fn get_trash_list(
+    extract::Path(instance_id): extract::Path<AddonInstanceUuid>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+    extract::Query(TrashQuery { offset, limit }): extract::Query<TrashQuery>,
+) -> Result<JsonListResponse<serde_json::Value>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    member.author(blog.id, &mut acq).await?;
+
+    let offset = offset.unwrap_or(0).max(0);
+    let limit = limit
+        .unwrap_or(DEFAULT_TRASH_LIMIT)
+        .clamp(1, MAX_TRASH_LIMIT);
+
+    let (posts, total) =
+        PostModel::find_deleted_by_blog_id(blog.id, offset, limit, &mut acq).await?;
+
+    let items = posts
+        .iter()
+        .map(|post| {
+            serde_json::json!({
+                "id": post.id,
+                "slug": post.slug,
+                "title": post.title,
+                "status": post.status,
+                "author_id": post.author_id,
+                "delete_reason": post.delete_reason,
+                "deleted_at": post.deleted_at,
+                "purge_at": post.deleted_at.map(|v| v + *TRASH_RETENTION),
+            })
+        })
+        .collect();
+
+    Ok(Json(WrappingResponse::okay(ListResponse {
+        offset,
+        limit,
+        total,
+        items,
+    })))
+}
+
+async fn restore_post(
+    extract::Path((instance_id, post_id)): extract::Path<(AddonInstanceUuid, PostId)>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+) -> Result<JsonResponse<serde_json::Value>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    let current = member.author(blog.id, &mut acq).await?;
+
+    let post = find_deleted_post(blog.id, post_id, &mut acq).await?;
+
+    check_can_edit_post(&current, &post, &mut acq).await?;
+
+    PostModel::restore(blog.id, post.id, &mut acq).await?;
+
+    Ok(Json(WrappingResponse::okay(serde_json::json!({
+        "id": post.id,
+        "slug": post.slug,
+    }))))
+}
+
+/// Permanently deletes the post without waiting for the retention period.
+async fn purge_post(
+    extract::Path((instance_id, post_id)): extract::Path<(AddonInstanceUuid, PostId)>,
+    extract::State(db): extract::State<SqlitePool>,
+    member: Member,
+) -> Result<JsonResponse<&'static str>> {
+    let mut acq = db.acquire().await?;
+
+    let Some(blog) = BlogModel::find_one_by_instance_id(instance_id, &mut acq).await? else {
+        return Err(Error::NotFound("Addon not found"));
+    };
+
+    member
+        .author_with(blog.id, AuthorRole::can_edit_others, &mut acq)
+        .await?;
+
+    let post = find_deleted_post(blog.id, post_id, &mut acq).await?;
+
+    PostModel::purge(blog.id, post.id, &mut acq).await?;
+
+    Ok(Json(WrappingResponse::okay("success")))
+}
@@ -95,3 +95,4 @@ create_id!(TagId, i64, ParseIntError);
 create_id!(CategoryId, i64, ParseIntError);
 create_id!(PostTagId, i64, ParseIntError);
 create_id!(PostCategoryId, i64, ParseIntError);
+create_id!(PostRevisionId, i64, ParseIntError);
@@ -28,5 +28,11 @@ pub async fn init() -> Result<(bool, SqlitePool)> {
         Err(error) => panic!("Migration Error: {error}"),
     }
 
+    let refreshed = models::PostModel::refresh_missing_text(&mut *pool.acquire().await?).await?;
+
+    if refreshed != 0 {
+        debug!("Derived text for {refreshed} post(s)");
+    }
+
     Ok((!does_db_exist, pool))
 }
@@ -1,39 +1,73 @@
-use webby_addon_common::{MemberUuid, WebsiteUuid};
+use webby_addon_common::MemberUuid;
 use eyre::Result;
-use serde::Serialize;
-use sqlx::{FromRow, SqliteConnection};
-use uuid::Uuid;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Json, FromRow, SqliteConnection};
+use time::OffsetDateTime;
 
-use crate::AuthorId;
+use crate::{AuthorId, BlogId, PostId};
 
 pub struct NewAuthorModel {
-    pub blog_id: WebsiteUuid,
+    pub blog_id: BlogId,
     pub external_member_id: MemberUuid,
 
     pub name: String,
+    pub slug: String,
     pub email: Option<String>,
+
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+    pub links: Vec<AuthorLink>,
+
+    pub role: AuthorRole,
 }
 
 #[derive(FromRow, Serialize)]
 pub struct AuthorModel {
     pub id: AuthorId,
 
-    pub blog_id: WebsiteUuid,
+    pub blog_id: BlogId,
     pub external_member_id: MemberUuid,
 
     pub name: String,
+    pub slug: String,
     pub email: Option<String>,
+
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+    pub links: Json<Vec<AuthorLink>>,
+
+    pub role: AuthorRole,
+
+    pub created_at: OffsetDateTime,
+    pub updated_at: OffsetDateTime,
+}
+
+/// Social or personal website link shown on the author's profile.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorLink {
+    /// eg. "Mastodon"
+    pub name: String,
+    pub url: String,
 }
 
 impl NewAuthorModel {
     pub async fn insert(self, db: &mut SqliteConnection) -> Result<AuthorModel> {
+        let now = OffsetDateTime::now_utc();
+
         let resp = sqlx::query(
-            "INSERT INTO author (blog_id, external_member_id, name, email) VALUES ($1, $2, $3, $4)",
+            "INSERT INTO author (blog_id, external_member_id, name, slug, email, bio, avatar_url, links, role, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $10)",
         )
         .bind(self.blog_id)
         .bind(self.external_member_id)
         .bind(&self.name)
+        .bind(&self.slug)
         .bind(&self.email)
+        .bind(&self.bio)
+        .bind(&self.avatar_url)
+        .bind(Json(&self.links))
+        .bind(self.role)
+        .bind(now)
         .execute(db)
         .await?;
 
@@ -42,31 +76,111 @@ impl NewAuthorModel {
             blog_id: self.blog_id,
             external_member_id: self.external_member_id,
             name: self.name,
-            email: None,
+            slug: self.slug,
+            email: self.email,
+            bio: self.bio,
+            avatar_url: self.avatar_url,
+            links: Json(self.links),
+            role: self.role,
+            created_at: now,
+            updated_at: now,
         })
     }
 }
 
 impl AuthorModel {
-    pub async fn find_one_by_guid(guid: Uuid, db: &mut SqliteConnection) -> Result<Option<Self>> {
+    pub async fn update(&mut self, db: &mut SqliteConnection) -> Result<u64> {
+        self.updated_at = OffsetDateTime::now_utc();
+
+        let res = sqlx::query(
+            "UPDATE author SET name = $2, slug = $3, email = $4, bio = $5, avatar_url = $6, links = $7, role = $8, updated_at = $9 WHERE id = $1 AND blog_id = $10",
+        )
+        .bind(self.id)
+        .bind(&self.name)
+        .bind(&self.slug)
+        .bind(&self.email)
+        .bind(&self.bio)
+        .bind(&self.avatar_url)
+        .bind(&self.links)
+        .bind(self.role)
+        .bind(self.updated_at)
+        .bind(self.blog_id)
+        .execute(db)
+        .await?;
+
+        Ok(res.rows_affected())
+    }
+
+    pub async fn find_one_by_id(
+        blog_id: BlogId,
+        id: AuthorId,
+        db: &mut SqliteConnection,
+    ) -> Result<Option<Self>> {
         Ok(sqlx::query_as(
-            "SELECT id, blog_id, external_member_id, name, email FROM author WHERE guid = $1",
+            "SELECT id, blog_id, external_member_id, name, slug, email, bio, avatar_url, links, role, created_at, updated_at FROM author WHERE blog_id = $1 AND id = $2",
         )
-        .bind(guid)
+        .bind(blog_id)
+        .bind(id)
         .fetch_optional(db)
         .await?)
     }
 
-    pub async fn find_all(db: &mut SqliteConnection) -> Result<Vec<Self>> {
-        Ok(
-            sqlx::query_as("SELECT id, blog_id, external_member_id, name, email FROM author")
-                .fetch_all(db)
-                .await?,
+    pub async fn find_one_by_slug(
+        blog_id: BlogId,
+        slug: &str,
+        db: &mut SqliteConnection,
+    ) -> Result<Option<Self>> {
+        Ok(sqlx::query_as(
+            "SELECT id, blog_id, external_member_id, name, slug, email, bio, avatar_url, links, role, created_at, updated_at FROM author WHERE blog_id = $1 AND slug = $2",
         )
+        .bind(blog_id)
+        .bind(slug)
+        .fetch_optional(db)
+        .await?)
     }
 
-    pub async fn delete(id: AuthorId, db: &mut SqliteConnection) -> Result<u64> {
-        let res = sqlx::query("DELETE FROM author WHERE id = $1")
+    pub async fn find_one_by_member_id(
+        blog_id: BlogId,
+        member_id: MemberUuid,
+        db: &mut SqliteConnection,
+    ) -> Result<Option<Self>> {
+        Ok(sqlx::query_as(
+            "SELECT id, blog_id, external_member_id, name, slug, email, bio, avatar_url, links, role, created_at, updated_at FROM author WHERE blog_id = $1 AND external_member_id = $2",
+        )
+        .bind(blog_id)
+        .bind(member_id)
+        .fetch_optional(db)
+        .await?)
+    }
+
+    pub async fn find_by_blog_id(id: BlogId, db: &mut SqliteConnection) -> Result<Vec<Self>> {
+        Ok(sqlx::query_as(
+            "SELECT id, blog_id, external_member_id, name, slug, email, bio, avatar_url, links, role, created_at, updated_at FROM author WHERE blog_id = $1 ORDER BY name",
+        )
+        .bind(id)
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Co-authors of the post in their listed order. Doesn't include the main author.
+    pub async fn find_by_post_id(
+        blog_id: BlogId,
+        post_id: PostId,
+        db: &mut SqliteConnection,
+    ) -> Result<Vec<Self>> {
+        Ok(sqlx::query_as(
+            "SELECT author.id, author.blog_id, author.external_member_id, author.name, author.slug, author.email, author.bio, author.avatar_url, author.links, author.role, author.created_at, author.updated_at FROM post_author INNER JOIN author ON author.id = post_author.author_id WHERE post_author.blog_id = $1 AND post_author.post_id = $2 ORDER BY post_author.position",
+        )
+        .bind(blog_id)
+        .bind(post_id)
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Posts keep existing without an author.
+    pub async fn delete(blog_id: BlogId, id: AuthorId, db: &mut SqliteConnection) -> Result<u64> {
+        let res = sqlx::query("DELETE FROM author WHERE blog_id = $1 AND id = $2")
+            .bind(blog_id)
             .bind(id)
             .execute(db)
             .await?;
@@ -74,3 +188,70 @@ impl AuthorModel {
         Ok(res.rows_affected())
     }
 }
+
+/// What a member can do on the blog, from most to least trusted.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    serde::Serialize,
+    serde::Deserialize,
+    IntoPrimitive,
+    TryFromPrimitive,
+)]
+#[repr(u8)]
+pub enum AuthorRole {
+    /// Installed the addon. Manages the other authors.
+    Owner = 0,
+    /// Publishes any post and moderates comments
+    Editor = 1,
+    /// Publishes their own posts
+    Author = 2,
+    /// Writes drafts of their own posts
+    Contributor = 3,
+}
+
+impl AuthorRole {
+    pub fn can_publish(self) -> bool {
+        !matches!(self, Self::Contributor)
+    }
+
+    /// Edit posts they aren't an author of and change post authors
+    pub fn can_edit_others(self) -> bool {
+        matches!(self, Self::Owner | Self::Editor)
+    }
+
+    pub fn can_moderate(self) -> bool {
+        matches!(self, Self::Owner | Self::Editor)
+    }
+
+    pub fn can_manage_authors(self) -> bool {
+        matches!(self, Self::Owner)
+    }
+}
+
+impl ::sqlx::Encode<'_, ::sqlx::sqlite::Sqlite> for AuthorRole {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <::sqlx::sqlite::Sqlite as sqlx::database::HasArguments<'_>>::ArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        ::sqlx::Encode::<::sqlx::sqlite::Sqlite>::encode_by_ref(&(*self as u8 as i32), buf)
+    }
+}
+
+impl ::sqlx::Decode<'_, ::sqlx::sqlite::Sqlite> for AuthorRole {
+    fn decode(
+        value: <::sqlx::sqlite::Sqlite as sqlx::database::HasValueRef<'_>>::ValueRef,
+    ) -> std::result::Result<Self, sqlx::error::BoxDynError> {
+        Ok(Self::try_from(
+            <i32 as ::sqlx::Decode<::sqlx::sqlite::Sqlite>>::decode(value)? as u8,
+        )?)
+    }
+}
+
+impl ::sqlx::Type<::sqlx::sqlite::Sqlite> for AuthorRole {
+    fn type_info() -> ::sqlx::sqlite::SqliteTypeInfo {
+        <i32 as ::sqlx::Type<::sqlx::sqlite::Sqlite>>::type_info()
+    }
+}
@@ -1,47 +1,164 @@
 use eyre::Result;
 use serde::Serialize;
 use sqlx::{FromRow, SqliteConnection};
-use uuid::Uuid;
+use time::OffsetDateTime;
 
-use crate::CategoryId;
+use crate::{BlogId, CategoryId, PostId};
 
 pub struct NewCategoryModel {
+    pub blog_id: BlogId,
+    pub parent_id: Option<CategoryId>,
+
     pub name: String,
+    pub slug: String,
+    pub description: Option<String>,
+
+    pub display_order: i32,
 }
 
 #[derive(FromRow, Serialize)]
 pub struct CategoryModel {
     pub id: CategoryId,
+
+    pub blog_id: BlogId,
+    pub parent_id: Option<CategoryId>,
+
     pub name: String,
+    pub slug: String,
+    pub description: Option<String>,
+
+    pub display_order: i32,
+
+    pub created_at: OffsetDateTime,
+    pub updated_at: OffsetDateTime,
 }
 
 impl NewCategoryModel {
     pub async fn insert(self, db: &mut SqliteConnection) -> Result<CategoryModel> {
-        let resp = sqlx::query("INSERT INTO category (name) VALUES ($1)")
-            .bind(&self.name)
-            .execute(db)
-            .await?;
+        let now = OffsetDateTime::now_utc();
+
+        let resp = sqlx::query(
+            "INSERT INTO category (blog_id, parent_id, name, slug, description, display_order, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $7)",
+        )
+        .bind(self.blog_id)
+        .bind(self.parent_id)
+        .bind(&self.name)
+        .bind(&self.slug)
+        .bind(&self.description)
+        .bind(self.display_order)
+        .bind(now)
+        .execute(db)
+        .await?;
 
         Ok(CategoryModel {
             id: CategoryId::from(resp.last_insert_rowid()),
+            blog_id: self.blog_id,
+            parent_id: self.parent_id,
             name: self.name,
+            slug: self.slug,
+            description: self.description,
+            display_order: self.display_order,
+            created_at: now,
+            updated_at: now,
         })
     }
 }
 
 impl CategoryModel {
-    pub async fn find_one_by_guid(guid: Uuid, db: &mut SqliteConnection) -> Result<Option<Self>> {
-        Ok(
-            sqlx::query_as("SELECT id, name FROM category WHERE guid = $1")
-                .bind(guid)
-                .fetch_optional(db)
-                .await?,
+    pub async fn update(&mut self, db: &mut SqliteConnection) -> Result<u64> {
+        self.updated_at = OffsetDateTime::now_utc();
+
+        let res = sqlx::query(
+            "UPDATE category SET parent_id = $2, name = $3, slug = $4, description = $5, display_order = $6, updated_at = $7 WHERE id = $1 AND blog_id = $8",
+        )
+        .bind(self.id)
+        .bind(self.parent_id)
+        .bind(&self.name)
+        .bind(&self.slug)
+        .bind(&self.description)
+        .bind(self.display_order)
+        .bind(self.updated_at)
+        .bind(self.blog_id)
+        .execute(db)
+        .await?;
+
+        Ok(res.rows_affected())
+    }
+
+    pub async fn find_one_by_id(
+        blog_id: BlogId,
+        id: CategoryId,
+        db: &mut SqliteConnection,
+    ) -> Result<Option<Self>> {
+        Ok(sqlx::query_as(
+            "SELECT id, blog_id, parent_id, name, slug, description, display_order, created_at, updated_at FROM category WHERE blog_id = $1 AND id = $2",
         )
+        .bind(blog_id)
+        .bind(id)
+        .fetch_optional(db)
+        .await?)
     }
 
-    pub async fn find_all(db: &mut SqliteConnection) -> Result<Vec<Self>> {
-        Ok(sqlx::query_as("SELECT id, name FROM category")
-            .fetch_all(db)
-            .await?)
+    pub async fn find_one_by_slug(
+        blog_id: BlogId,
+        slug: &str,
+        db: &mut SqliteConnection,
+    ) -> Result<Option<Self>> {
+        Ok(sqlx::query_as(
+            "SELECT id, blog_id, parent_id, name, slug, description, display_order, created_at, updated_at FROM category WHERE blog_id = $1 AND slug = $2",
+        )
+        .bind(blog_id)
+        .bind(slug)
+        .fetch_optional(db)
+        .await?)
+    }
+
+    /// Case-insensitive. Categories under different parents may share a name, the oldest wins.
+    pub async fn find_one_by_name(
+        blog_id: BlogId,
+        name: &str,
+        db: &mut SqliteConnection,
+    ) -> Result<Option<Self>> {
+        Ok(sqlx::query_as(
+            "SELECT id, blog_id, parent_id, name, slug, description, display_order, created_at, updated_at FROM category WHERE blog_id = $1 AND name = $2 COLLATE NOCASE ORDER BY id LIMIT 1",
+        )
+        .bind(blog_id)
+        .bind(name)
+        .fetch_optional(db)
+        .await?)
+    }
+
+    pub async fn find_by_blog_id(id: BlogId, db: &mut SqliteConnection) -> Result<Vec<Self>> {
+        Ok(sqlx::query_as(
+            "SELECT id, blog_id, parent_id, name, slug, description, display_order, created_at, updated_at FROM category WHERE blog_id = $1 ORDER BY display_order, name",
+        )
+        .bind(id)
+        .fetch_all(db)
+        .await?)
+    }
+
+    pub async fn find_by_post_id(
+        blog_id: BlogId,
+        post_id: PostId,
+        db: &mut SqliteConnection,
+    ) -> Result<Vec<Self>> {
+        Ok(sqlx::query_as(
+            "SELECT category.id, category.blog_id, category.parent_id, category.name, category.slug, category.description, category.display_order, category.created_at, category.updated_at FROM category INNER JOIN post_category ON post_category.category_id = category.id WHERE post_category.blog_id = $1 AND post_category.post_id = $2 AND category.blog_id = $1 ORDER BY category.display_order, category.name",
+        )
+        .bind(blog_id)
+        .bind(post_id)
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Children are moved up to the top level, posts lose the category.
+    pub async fn delete(blog_id: BlogId, id: CategoryId, db: &mut SqliteConnection) -> Result<u64> {
+        let res = sqlx::query("DELETE FROM category WHERE blog_id = $1 AND id = $2")
+            .bind(blog_id)
+            .bind(id)
+            .execute(db)
+            .await?;
+
+        Ok(res.rows_affected())
     }
 }
@@ -2,16 +2,18 @@ use webby_addon_common::MemberUuid;
 use eyre::Result;
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 use serde::Serialize;
-use sqlx::{FromRow, SqliteConnection};
+use sqlx::{types::Json, FromRow, QueryBuilder, Sqlite, SqliteConnection};
 use time::OffsetDateTime;
-use uuid::Uuid;
 
-use crate::{BlogId, CommentId, PostId};
+use crate::{spam::SpamSignal, BlogId, CommentId, PostId};
 
 pub struct NewCommentModel {
     pub blog_id: BlogId,
     pub post_id: PostId,
 
+    pub parent_id: Option<CommentId>,
+    pub depth: i32,
+
     pub external_member_id: Option<MemberUuid>,
 
     pub author_name: String,
@@ -19,6 +21,9 @@ pub struct NewCommentModel {
     pub comment: String,
 
     pub status: CommentStatus,
+
+    pub spam_score: f64,
+    pub spam_reasons: Vec<SpamSignal>,
 }
 
 #[derive(FromRow, Serialize)]
@@ -28,6 +33,9 @@ pub struct CommentModel {
     pub blog_id: BlogId,
     pub post_id: PostId,
 
+    pub parent_id: Option<CommentId>,
+    pub depth: i32,
+
     pub external_member_id: Option<MemberUuid>,
 
     pub author_name: String,
@@ -36,7 +44,17 @@ pub struct CommentModel {
 
     pub status: CommentStatus,
 
+    pub spam_score: f64,
+    pub spam_reasons: Option<Json<Vec<SpamSignal>>>,
+    /// Which way the comment trained the spam classifier
+    pub trained_spam: Option<bool>,
+
     pub delete_reason: Option<String>,
+
+    pub created_at: OffsetDateTime,
+    pub updated_at: OffsetDateTime,
+    /// Last time the author name or text was changed
+    pub edited_at: Option<OffsetDateTime>,
     pub deleted_at: Option<OffsetDateTime>,
 }
 
@@ -45,15 +63,19 @@ impl NewCommentModel {
         let now = OffsetDateTime::now_utc();
 
         let resp = sqlx::query(
-            "INSERT INTO comment (blog_id, post_id, external_member_id, author_name, email, comment, status, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $8)",
+            "INSERT INTO comment (blog_id, post_id, parent_id, depth, external_member_id, author_name, email, comment, status, spam_score, spam_reasons, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $12)",
         )
             .bind(self.blog_id)
             .bind(self.post_id)
+            .bind(self.parent_id)
+            .bind(self.depth)
             .bind(self.external_member_id)
             .bind(&self.author_name)
             .bind(&self.email)
             .bind(&self.comment)
             .bind(self.status)
+            .bind(self.spam_score)
+            .bind(Json(&self.spam_reasons))
             .bind(now)
             .execute(db)
             .await?;
@@ -62,58 +84,224 @@ impl NewCommentModel {
             id: CommentId::from(resp.last_insert_rowid()),
             blog_id: self.blog_id,
             post_id: self.post_id,
+            parent_id: self.parent_id,
+            depth: self.depth,
             external_member_id: self.external_member_id,
             author_name: self.author_name,
             email: self.email,
             comment: self.comment,
             status: self.status,
+            spam_score: self.spam_score,
+            spam_reasons: Some(Json(self.spam_reasons)),
+            trained_spam: None,
             delete_reason: None,
+            created_at: now,
+            updated_at: now,
+            edited_at: None,
             deleted_at: None,
         })
     }
 }
 
 impl CommentModel {
-    pub async fn find_one_by_guid(guid: Uuid, db: &mut SqliteConnection) -> Result<Option<Self>> {
+    /// Saves the author name and text, marking the comment as edited.
+    pub async fn update(&mut self, db: &mut SqliteConnection) -> Result<u64> {
+        let now = OffsetDateTime::now_utc();
+
+        self.updated_at = now;
+        self.edited_at = Some(now);
+
+        let res = sqlx::query(
+            "UPDATE comment SET author_name = $2, comment = $3, updated_at = $4, edited_at = $4 WHERE id = $1 AND blog_id = $5",
+        )
+        .bind(self.id)
+        .bind(&self.author_name)
+        .bind(&self.comment)
+        .bind(now)
+        .bind(self.blog_id)
+        .execute(db)
+        .await?;
+
+        Ok(res.rows_affected())
+    }
+
+    pub async fn find_one_by_id(
+        blog_id: BlogId,
+        id: CommentId,
+        db: &mut SqliteConnection,
+    ) -> Result<Option<Self>> {
         Ok(sqlx::query_as(
-            "SELECT id, blog_id, post_id, external_member_id, author_name, email, comment, status, delete_reason, deleted_at FROM comment WHERE guid = $1"
+            "SELECT id, blog_id, post_id, parent_id, depth, external_member_id, author_name, email, comment, status, spam_score, spam_reasons, trained_spam, delete_reason, created_at, updated_at, edited_at, deleted_at FROM comment WHERE blog_id = $1 AND id = $2"
         )
-        .bind(guid)
+        .bind(blog_id)
+        .bind(id)
         .fetch_optional(db)
         .await?)
     }
 
-    pub async fn find_all(db: &mut SqliteConnection) -> Result<Vec<Self>> {
+    /// Every comment of the post regardless of status, oldest first.
+    pub async fn find_by_post_id(
+        blog_id: BlogId,
+        post_id: PostId,
+        db: &mut SqliteConnection,
+    ) -> Result<Vec<Self>> {
         Ok(sqlx::query_as(
-            "SELECT id, blog_id, post_id, external_member_id, author_name, email, comment, status, delete_reason, deleted_at FROM comment"
+            "SELECT id, blog_id, post_id, parent_id, depth, external_member_id, author_name, email, comment, status, spam_score, spam_reasons, trained_spam, delete_reason, created_at, updated_at, edited_at, deleted_at FROM comment WHERE blog_id = $1 AND post_id = $2 ORDER BY created_at, id"
         )
+        .bind(blog_id)
+        .bind(post_id)
         .fetch_all(db)
         .await?)
     }
 
-    pub async fn delete(
+    /// Non-deleted comments of the blog for moderation, newest first.
+    pub async fn find_by_blog_id(
+        id: BlogId,
+        filter: &CommentFilter,
+        offset: i64,
+        limit: i64,
+        db: &mut SqliteConnection,
+    ) -> Result<(Vec<Self>, i64)> {
+        let mut builder = QueryBuilder::new("SELECT COUNT(*) FROM comment");
+        filter.push_filters(id, &mut builder);
+
+        let total = builder.build_query_scalar().fetch_one(&mut *db).await?;
+
+        let mut builder = QueryBuilder::new(
+            "SELECT id, blog_id, post_id, parent_id, depth, external_member_id, author_name, email, comment, status, spam_score, spam_reasons, trained_spam, delete_reason, created_at, updated_at, edited_at, deleted_at FROM comment",
+        );
+        filter.push_filters(id, &mut builder);
+
+        builder
+            .push(" ORDER BY created_at DESC, id DESC LIMIT ")
+            .push_bind(limit)
+            .push(" OFFSET ")
+            .push_bind(offset);
+
+        let items = builder.build_query_as().fetch_all(db).await?;
+
+        Ok((items, total))
+    }
+
+    pub async fn update_status(
+        blog_id: BlogId,
         id: CommentId,
-        reason: Option<String>,
+        status: CommentStatus,
+        db: &mut SqliteConnection,
+    ) -> Result<u64> {
+        let res = sqlx::query(
+            "UPDATE comment SET status = $2, updated_at = $3 WHERE id = $1 AND blog_id = $4",
+        )
+        .bind(id)
+        .bind(status)
+        .bind(OffsetDateTime::now_utc())
+        .bind(blog_id)
+        .execute(db)
+        .await?;
+
+        Ok(res.rows_affected())
+    }
+
+    /// Saves the outcome of the spam checks, after the comment was edited.
+    pub async fn update_spam(&self, db: &mut SqliteConnection) -> Result<u64> {
+        let res = sqlx::query(
+            "UPDATE comment SET status = $2, spam_score = $3, spam_reasons = $4 WHERE id = $1 AND blog_id = $5",
+        )
+        .bind(self.id)
+        .bind(self.status)
+        .bind(self.spam_score)
+        .bind(&self.spam_reasons)
+        .bind(self.blog_id)
+        .execute(db)
+        .await?;
+
+        Ok(res.rows_affected())
+    }
+
+    pub async fn update_trained_spam(
+        blog_id: BlogId,
+        id: CommentId,
+        trained_spam: Option<bool>,
         db: &mut SqliteConnection,
     ) -> Result<u64> {
         let res =
-            sqlx::query("UPDATE comment SET deleted_at = $2, delete_reason = $3 WHERE id = $1")
+            sqlx::query("UPDATE comment SET trained_spam = $2 WHERE id = $1 AND blog_id = $3")
                 .bind(id)
-                .bind(OffsetDateTime::now_utc())
-                .bind(reason)
+                .bind(trained_spam)
+                .bind(blog_id)
                 .execute(db)
                 .await?;
 
         Ok(res.rows_affected())
     }
+
+    pub async fn count_approved_by_post_id(
+        blog_id: BlogId,
+        post_id: PostId,
+        db: &mut SqliteConnection,
+    ) -> Result<i64> {
+        Ok(sqlx::query_scalar(
+            "SELECT COUNT(*) FROM comment WHERE post_id = $1 AND status = $2 AND blog_id = $3 AND deleted_at IS NULL",
+        )
+        .bind(post_id)
+        .bind(CommentStatus::Approved)
+        .bind(blog_id)
+        .fetch_one(db)
+        .await?)
+    }
+
+    pub async fn delete(
+        blog_id: BlogId,
+        id: CommentId,
+        reason: Option<String>,
+        db: &mut SqliteConnection,
+    ) -> Result<u64> {
+        let res = sqlx::query(
+            "UPDATE comment SET deleted_at = $2, updated_at = $2, delete_reason = $3 WHERE id = $1 AND blog_id = $4",
+        )
+        .bind(id)
+        .bind(OffsetDateTime::now_utc())
+        .bind(reason)
+        .bind(blog_id)
+        .execute(db)
+        .await?;
+
+        Ok(res.rows_affected())
+    }
+}
+
+#[derive(Default)]
+pub struct CommentFilter {
+    pub status: Option<CommentStatus>,
+    pub post_id: Option<PostId>,
+}
+
+impl CommentFilter {
+    fn push_filters<'a>(&'a self, id: BlogId, builder: &mut QueryBuilder<'a, Sqlite>) {
+        builder
+            .push(" WHERE deleted_at IS NULL AND blog_id = ")
+            .push_bind(id);
+
+        if let Some(status) = self.status {
+            builder.push(" AND status = ").push_bind(status);
+        }
+
+        if let Some(post_id) = self.post_id {
+            builder.push(" AND post_id = ").push_bind(post_id);
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy, serde::Serialize, IntoPrimitive, TryFromPrimitive)]
+#[derive(
+    Debug, Clone, Copy, serde::Serialize, serde::Deserialize, IntoPrimitive, TryFromPrimitive,
+)]
 #[repr(u8)]
 pub enum CommentStatus {
     Pending = 0,
     Approved = 1,
     Denied = 2,
+    /// Flagged by the spam checks, waiting for a moderator
+    Spam = 3,
 }
 
 impl FromRow<'_, ::sqlx::sqlite::SqliteRow> for CommentStatus {
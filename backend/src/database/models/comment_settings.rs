@@ -0,0 +1,84 @@
+use eyre::Result;
+use serde::Serialize;
+use sqlx::{FromRow, SqliteConnection};
+use time::{Duration, OffsetDateTime};
+
+use super::PostModel;
+use crate::BlogId;
+
+pub const DEFAULT_COMMENT_MAX_DEPTH: i32 = 3;
+
+#[derive(FromRow, Serialize)]
+pub struct CommentSettingsModel {
+    pub blog_id: BlogId,
+
+    /// Deepest reply level allowed. 0 disables replies.
+    pub max_depth: i32,
+
+    /// Posts can override this with `comments_enabled`.
+    pub enabled: bool,
+    /// Comments from signed in members skip moderation.
+    pub auto_approve_members: bool,
+    /// Days after the post date when comments close. None keeps them open.
+    pub close_after_days: Option<i32>,
+    pub require_email: bool,
+
+    pub updated_at: OffsetDateTime,
+}
+
+impl CommentSettingsModel {
+    pub fn new(blog_id: BlogId) -> Self {
+        Self {
+            blog_id,
+            max_depth: DEFAULT_COMMENT_MAX_DEPTH,
+            enabled: true,
+            auto_approve_members: false,
+            close_after_days: None,
+            require_email: false,
+            updated_at: OffsetDateTime::now_utc(),
+        }
+    }
+
+    /// Defaults for blogs which never saved their settings.
+    pub async fn find_one_by_blog_id(id: BlogId, db: &mut SqliteConnection) -> Result<Self> {
+        let found = sqlx::query_as(
+            "SELECT blog_id, max_depth, enabled, auto_approve_members, close_after_days, require_email, updated_at FROM comment_settings WHERE blog_id = $1",
+        )
+        .bind(id)
+        .fetch_optional(db)
+        .await?;
+
+        Ok(found.unwrap_or_else(|| Self::new(id)))
+    }
+
+    /// Whether the post currently accepts new comments.
+    pub fn is_open_for(&self, post: &PostModel, now: OffsetDateTime) -> bool {
+        if !post.comments_enabled.unwrap_or(self.enabled) {
+            return false;
+        }
+
+        match self.close_after_days {
+            Some(days) => now < post.post_date + Duration::days(days as i64),
+            None => true,
+        }
+    }
+
+    pub async fn save(&mut self, db: &mut SqliteConnection) -> Result<u64> {
+        self.updated_at = OffsetDateTime::now_utc();
+
+        let res = sqlx::query(
+            "INSERT INTO comment_settings (blog_id, max_depth, enabled, auto_approve_members, close_after_days, require_email, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7) ON CONFLICT (blog_id) DO UPDATE SET max_depth = excluded.max_depth, enabled = excluded.enabled, auto_approve_members = excluded.auto_approve_members, close_after_days = excluded.close_after_days, require_email = excluded.require_email, updated_at = excluded.updated_at",
+        )
+        .bind(self.blog_id)
+        .bind(self.max_depth)
+        .bind(self.enabled)
+        .bind(self.auto_approve_members)
+        .bind(self.close_after_days)
+        .bind(self.require_email)
+        .bind(self.updated_at)
+        .execute(db)
+        .await?;
+
+        Ok(res.rows_affected())
+    }
+}
@@ -2,16 +2,28 @@ mod author;
 mod blog;
 mod category;
 mod comment;
+mod comment_settings;
 mod post;
+mod post_author;
 mod post_category;
+mod post_revision;
+mod post_search;
+mod post_slug_history;
 mod post_tag;
+mod spam;
 mod tag;
 
 pub use author::*;
 pub use blog::*;
 pub use category::*;
 pub use comment::*;
+pub use comment_settings::*;
 pub use post::*;
+pub use post_author::*;
 pub use post_category::*;
+pub use post_revision::*;
+pub use post_search::*;
+pub use post_slug_history::*;
 pub use post_tag::*;
+pub use spam::*;
 pub use tag::*;
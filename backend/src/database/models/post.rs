@@ -1,22 +1,33 @@
+use std::str::FromStr;
+
 use eyre::Result;
 use num_enum::{IntoPrimitive, TryFromPrimitive};
-use regex::Regex;
 use serde::Serialize;
-use sqlx::{types::Json, FromRow, SqliteConnection};
+use sqlx::{types::Json, FromRow, QueryBuilder, Sqlite, SqliteConnection};
 use time::OffsetDateTime;
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
 
-use crate::{BlogId, PostId};
+use crate::{
+    delta::{text, Delta},
+    AuthorId, BlogId, PostId,
+};
 
 pub struct NewPostModel {
     pub blog_id: BlogId,
+    /// Main author. Co-authors are in `post_author`.
+    pub author_id: Option<AuthorId>,
 
     pub title: String,
     pub content: serde_json::Value,
     pub slug: Option<String>,
+    pub custom_excerpt: Option<String>,
 
     pub status: PostStatus,
 
     pub post_date: Option<OffsetDateTime>,
+
+    /// Overrides the blog's comment setting when set
+    pub comments_enabled: Option<bool>,
 }
 
 #[derive(FromRow, Serialize)]
@@ -24,6 +35,8 @@ pub struct PostModel {
     pub id: PostId,
 
     pub blog_id: BlogId,
+    /// Main author. Co-authors are in `post_author`.
+    pub author_id: Option<AuthorId>,
 
     pub title: String,
     pub content: Json<serde_json::Value>,
@@ -34,6 +47,16 @@ pub struct PostModel {
 
     pub post_date: OffsetDateTime,
 
+    #[serde(skip)]
+    pub plain_text: String,
+    pub excerpt: String,
+    pub custom_excerpt: Option<String>,
+    pub word_count: i64,
+    pub read_minutes: i64,
+
+    /// Overrides the blog's comment setting when set
+    pub comments_enabled: Option<bool>,
+
     pub delete_reason: Option<String>,
 
     pub created_at: OffsetDateTime,
@@ -45,16 +68,24 @@ impl NewPostModel {
     pub async fn insert(self, db: &mut SqliteConnection) -> Result<PostModel> {
         let now = OffsetDateTime::now_utc();
         let post_date = self.post_date.unwrap_or(now);
+        let text = DerivedText::from_content(&self.content);
 
         let resp = sqlx::query(
-            "INSERT INTO post (blog_id, title, content, slug, status, post_date, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $7)",
+            "INSERT INTO post (blog_id, author_id, title, content, slug, status, post_date, plain_text, excerpt, custom_excerpt, word_count, read_minutes, comments_enabled, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $14)",
         )
         .bind(self.blog_id)
+        .bind(self.author_id)
         .bind(&self.title)
         .bind(Json(&self.content))
         .bind(&self.slug)
         .bind(self.status)
         .bind(post_date)
+        .bind(&text.plain_text)
+        .bind(&text.excerpt)
+        .bind(&self.custom_excerpt)
+        .bind(text.word_count)
+        .bind(text.read_minutes)
+        .bind(self.comments_enabled)
         .bind(now)
         .execute(db)
         .await?;
@@ -62,11 +93,18 @@ impl NewPostModel {
         Ok(PostModel {
             id: PostId::from(resp.last_insert_rowid()),
             blog_id: self.blog_id,
+            author_id: self.author_id,
             title: self.title,
             content: Json(self.content),
             slug: self.slug,
             status: self.status as u8 as i32,
             post_date,
+            plain_text: text.plain_text,
+            excerpt: text.excerpt,
+            custom_excerpt: self.custom_excerpt,
+            word_count: text.word_count,
+            read_minutes: text.read_minutes,
+            comments_enabled: self.comments_enabled,
             delete_reason: None,
             created_at: now,
             updated_at: now,
@@ -79,48 +117,307 @@ impl PostModel {
     pub async fn update(&mut self, db: &mut SqliteConnection) -> Result<u64> {
         self.updated_at = OffsetDateTime::now_utc();
 
+        let text = DerivedText::from_content(&self.content);
+
+        self.plain_text = text.plain_text;
+        self.excerpt = text.excerpt;
+        self.word_count = text.word_count;
+        self.read_minutes = text.read_minutes;
+
         let res =
-            sqlx::query("UPDATE post SET title = $2, content = $3, slug = $4, status = $5, post_date = $6, updated_at = $7 WHERE id = $1")
+            sqlx::query("UPDATE post SET title = $2, content = $3, slug = $4, status = $5, post_date = $6, plain_text = $7, excerpt = $8, custom_excerpt = $9, word_count = $10, read_minutes = $11, comments_enabled = $12, author_id = $13, updated_at = $14 WHERE id = $1 AND blog_id = $15")
                 .bind(self.id)
                 .bind(&self.title)
                 .bind(&self.content)
                 .bind(&self.slug)
                 .bind(self.status)
                 .bind(self.post_date)
+                .bind(&self.plain_text)
+                .bind(&self.excerpt)
+                .bind(&self.custom_excerpt)
+                .bind(self.word_count)
+                .bind(self.read_minutes)
+                .bind(self.comments_enabled)
+                .bind(self.author_id)
                 .bind(self.updated_at)
+                .bind(self.blog_id)
                 .execute(db)
                 .await?;
 
         Ok(res.rows_affected())
     }
 
-    pub async fn find_one_by_id(id: PostId, db: &mut SqliteConnection) -> Result<Option<Self>> {
+    /// Author supplied excerpt, falling back to the generated one.
+    pub fn display_excerpt(&self) -> &str {
+        self.custom_excerpt
+            .as_deref()
+            .filter(|v| !v.trim().is_empty())
+            .unwrap_or(&self.excerpt)
+    }
+
+    /// Published, past its post date and not deleted.
+    pub fn is_public(&self) -> bool {
+        self.status == PostStatus::Published as u8 as i32
+            && self.post_date <= OffsetDateTime::now_utc()
+            && self.deleted_at.is_none()
+    }
+
+    /// Fills in the derived text columns for posts saved before they existed.
+    pub async fn refresh_missing_text(db: &mut SqliteConnection) -> Result<u64> {
+        let missing: Vec<(PostId, Json<serde_json::Value>)> =
+            sqlx::query_as("SELECT id, content FROM post WHERE word_count = 0 AND plain_text = ''")
+                .fetch_all(&mut *db)
+                .await?;
+
+        let mut updated = 0;
+
+        for (id, content) in missing {
+            let text = DerivedText::from_content(&content);
+
+            if text.word_count == 0 {
+                continue;
+            }
+
+            updated += sqlx::query(
+                "UPDATE post SET plain_text = $2, excerpt = $3, word_count = $4, read_minutes = $5 WHERE id = $1",
+            )
+            .bind(id)
+            .bind(&text.plain_text)
+            .bind(&text.excerpt)
+            .bind(text.word_count)
+            .bind(text.read_minutes)
+            .execute(&mut *db)
+            .await?
+            .rows_affected();
+        }
+
+        Ok(updated)
+    }
+
+    /// Slugs of the blog's posts which are `slug` or `slug-{suffix}`, deleted posts included.
+    pub async fn find_slugs_like(
+        blog_id: BlogId,
+        slug: &str,
+        except: Option<PostId>,
+        db: &mut SqliteConnection,
+    ) -> Result<Vec<String>> {
+        Ok(sqlx::query_scalar(
+            "SELECT slug FROM post WHERE blog_id = $1 AND (slug = $2 OR slug LIKE $3) AND id IS NOT $4",
+        )
+        .bind(blog_id)
+        .bind(slug)
+        .bind(format!("{slug}-%"))
+        .bind(except)
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Posts in the trash aren't included.
+    pub async fn find_one_by_id(
+        blog_id: BlogId,
+        id: PostId,
+        db: &mut SqliteConnection,
+    ) -> Result<Option<Self>> {
         Ok(sqlx::query_as(
-            "SELECT id, blog_id, title, content, slug, status, post_date, delete_reason, created_at, updated_at, deleted_at FROM post WHERE id = $1"
+            "SELECT id, blog_id, author_id, title, content, slug, status, post_date, plain_text, excerpt, custom_excerpt, word_count, read_minutes, comments_enabled, delete_reason, created_at, updated_at, deleted_at FROM post WHERE blog_id = $1 AND id = $2 AND deleted_at IS NULL"
         )
+        .bind(blog_id)
         .bind(id)
         .fetch_optional(db)
         .await?)
     }
 
+    pub async fn find_one_by_slug(
+        blog_id: BlogId,
+        slug: &str,
+        db: &mut SqliteConnection,
+    ) -> Result<Option<Self>> {
+        Ok(sqlx::query_as(
+            "SELECT id, blog_id, author_id, title, content, slug, status, post_date, plain_text, excerpt, custom_excerpt, word_count, read_minutes, comments_enabled, delete_reason, created_at, updated_at, deleted_at FROM post WHERE blog_id = $1 AND slug = $2"
+        )
+        .bind(blog_id)
+        .bind(slug)
+        .fetch_optional(db)
+        .await?)
+    }
+
+    /// Posts in the trash aren't included.
     pub async fn find_by_blog_id(id: BlogId, db: &mut SqliteConnection) -> Result<Vec<Self>> {
         Ok(sqlx::query_as(
-            "SELECT id, blog_id, title, content, slug, status, post_date, delete_reason, created_at, updated_at, deleted_at FROM post WHERE blog_id = $1"
+            "SELECT id, blog_id, author_id, title, content, slug, status, post_date, plain_text, excerpt, custom_excerpt, word_count, read_minutes, comments_enabled, delete_reason, created_at, updated_at, deleted_at FROM post WHERE blog_id = $1 AND deleted_at IS NULL"
+        )
+        .bind(id)
+        .fetch_all(db)
+        .await?)
+    }
+
+    pub async fn find_published_by_blog_id(
+        id: BlogId,
+        db: &mut SqliteConnection,
+    ) -> Result<Vec<Self>> {
+        Ok(sqlx::query_as(
+            "SELECT id, blog_id, author_id, title, content, slug, status, post_date, plain_text, excerpt, custom_excerpt, word_count, read_minutes, comments_enabled, delete_reason, created_at, updated_at, deleted_at FROM post WHERE blog_id = $1 AND status = $2 AND post_date <= $3 AND deleted_at IS NULL ORDER BY post_date DESC"
         )
         .bind(id)
+        .bind(PostStatus::Published)
+        .bind(OffsetDateTime::now_utc())
         .fetch_all(db)
         .await?)
     }
 
+    /// Flips every scheduled post whose `post_date` has passed to published.
+    pub async fn publish_scheduled(db: &mut SqliteConnection) -> Result<u64> {
+        let now = OffsetDateTime::now_utc();
+
+        let res = sqlx::query(
+            "UPDATE post SET status = $1, updated_at = $3 WHERE status = $2 AND post_date <= $3 AND deleted_at IS NULL",
+        )
+        .bind(PostStatus::Published)
+        .bind(PostStatus::Scheduled)
+        .bind(now)
+        .execute(db)
+        .await?;
+
+        Ok(res.rows_affected())
+    }
+
+    pub async fn find_by_query(
+        id: BlogId,
+        query: &PostQuery,
+        db: &mut SqliteConnection,
+    ) -> Result<(Vec<Self>, i64)> {
+        let mut builder = QueryBuilder::new("SELECT COUNT(*) FROM post");
+        query.push_filters(id, &mut builder);
+
+        let total = builder.build_query_scalar().fetch_one(&mut *db).await?;
+
+        let mut builder = QueryBuilder::new(
+            "SELECT id, blog_id, author_id, title, content, slug, status, post_date, plain_text, excerpt, custom_excerpt, word_count, read_minutes, comments_enabled, delete_reason, created_at, updated_at, deleted_at FROM post",
+        );
+        query.push_filters(id, &mut builder);
+
+        builder.push(" ORDER BY ");
+
+        for (sort, order) in &query.sort {
+            builder.push(sort.column()).push(match order {
+                SortOrder::Asc => " ASC, ",
+                SortOrder::Desc => " DESC, ",
+            });
+        }
+
+        builder
+            .push("id DESC LIMIT ")
+            .push_bind(query.limit)
+            .push(" OFFSET ")
+            .push_bind(query.offset);
+
+        let items = builder.build_query_as().fetch_all(db).await?;
+
+        Ok((items, total))
+    }
+
+    pub async fn count_by_blog_id(id: BlogId, db: &mut SqliteConnection) -> Result<i64> {
+        Ok(
+            sqlx::query_scalar("SELECT COUNT(*) FROM post WHERE blog_id = $1")
+                .bind(id)
+                .fetch_one(db)
+                .await?,
+        )
+    }
+
+    /// Moves the post to the trash.
     pub async fn delete(
+        blog_id: BlogId,
         id: PostId,
         reason: Option<String>,
         db: &mut SqliteConnection,
     ) -> Result<u64> {
-        let res = sqlx::query("UPDATE post SET deleted_at = $2, delete_reason = $3 WHERE id = $1")
+        let res = sqlx::query(
+            "UPDATE post SET deleted_at = $2, delete_reason = $3 WHERE id = $1 AND blog_id = $4 AND deleted_at IS NULL",
+        )
             .bind(id)
             .bind(OffsetDateTime::now_utc())
             .bind(reason)
+            .bind(blog_id)
+            .execute(db)
+            .await?;
+
+        Ok(res.rows_affected())
+    }
+
+    pub async fn find_one_deleted_by_id(
+        blog_id: BlogId,
+        id: PostId,
+        db: &mut SqliteConnection,
+    ) -> Result<Option<Self>> {
+        Ok(sqlx::query_as(
+            "SELECT id, blog_id, author_id, title, content, slug, status, post_date, plain_text, excerpt, custom_excerpt, word_count, read_minutes, comments_enabled, delete_reason, created_at, updated_at, deleted_at FROM post WHERE blog_id = $1 AND id = $2 AND deleted_at IS NOT NULL"
+        )
+        .bind(blog_id)
+        .bind(id)
+        .fetch_optional(db)
+        .await?)
+    }
+
+    /// Posts in the trash, most recently deleted first, along with the total count.
+    pub async fn find_deleted_by_blog_id(
+        id: BlogId,
+        offset: i64,
+        limit: i64,
+        db: &mut SqliteConnection,
+    ) -> Result<(Vec<Self>, i64)> {
+        let total = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM post WHERE blog_id = $1 AND deleted_at IS NOT NULL",
+        )
+        .bind(id)
+        .fetch_one(&mut *db)
+        .await?;
+
+        let items = sqlx::query_as(
+            "SELECT id, blog_id, author_id, title, content, slug, status, post_date, plain_text, excerpt, custom_excerpt, word_count, read_minutes, comments_enabled, delete_reason, created_at, updated_at, deleted_at FROM post WHERE blog_id = $1 AND deleted_at IS NOT NULL ORDER BY deleted_at DESC LIMIT $2 OFFSET $3"
+        )
+        .bind(id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(db)
+        .await?;
+
+        Ok((items, total))
+    }
+
+    /// Takes the post back out of the trash.
+    pub async fn restore(blog_id: BlogId, id: PostId, db: &mut SqliteConnection) -> Result<u64> {
+        let res = sqlx::query(
+            "UPDATE post SET deleted_at = NULL, delete_reason = NULL WHERE blog_id = $1 AND id = $2 AND deleted_at IS NOT NULL",
+        )
+        .bind(blog_id)
+        .bind(id)
+        .execute(db)
+        .await?;
+
+        Ok(res.rows_affected())
+    }
+
+    /// Permanently removes a post in the trash along with its comments, revisions and terms.
+    pub async fn purge(blog_id: BlogId, id: PostId, db: &mut SqliteConnection) -> Result<u64> {
+        let res = sqlx::query(
+            "DELETE FROM post WHERE blog_id = $1 AND id = $2 AND deleted_at IS NOT NULL",
+        )
+        .bind(blog_id)
+        .bind(id)
+        .execute(db)
+        .await?;
+
+        Ok(res.rows_affected())
+    }
+
+    /// Permanently removes every post which went in the trash before `deleted_before`.
+    pub async fn purge_deleted_before(
+        deleted_before: OffsetDateTime,
+        db: &mut SqliteConnection,
+    ) -> Result<u64> {
+        let res = sqlx::query("DELETE FROM post WHERE deleted_at IS NOT NULL AND deleted_at < $1")
+            .bind(deleted_before)
             .execute(db)
             .await?;
 
@@ -128,6 +425,141 @@ impl PostModel {
     }
 }
 
+struct DerivedText {
+    plain_text: String,
+    excerpt: String,
+    word_count: i64,
+    read_minutes: i64,
+}
+
+impl DerivedText {
+    fn from_content(content: &serde_json::Value) -> Self {
+        let plain_text = text::plain_text(&Delta::from_value(content));
+        let word_count = text::word_count(&plain_text);
+
+        Self {
+            excerpt: text::excerpt(&plain_text, text::EXCERPT_LENGTH),
+            word_count: word_count as i64,
+            read_minutes: text::reading_minutes(word_count) as i64,
+            plain_text,
+        }
+    }
+}
+
+pub struct PostQuery {
+    pub status: Option<PostStatus>,
+    /// Category slug
+    pub category: Option<String>,
+    /// Tag slug
+    pub tag: Option<String>,
+    /// Author slug, matching both main and co-authors
+    pub author: Option<String>,
+
+    pub from: Option<OffsetDateTime>,
+    pub to: Option<OffsetDateTime>,
+    /// Hide posts with a `post_date` after this
+    pub visible_at: Option<OffsetDateTime>,
+
+    pub sort: Vec<(PostSort, SortOrder)>,
+
+    pub offset: i64,
+    pub limit: i64,
+}
+
+impl PostQuery {
+    fn push_filters<'a>(&'a self, id: BlogId, builder: &mut QueryBuilder<'a, Sqlite>) {
+        builder
+            .push(" WHERE deleted_at IS NULL AND blog_id = ")
+            .push_bind(id);
+
+        if let Some(status) = self.status {
+            builder.push(" AND status = ").push_bind(status);
+        }
+
+        if let Some(category) = self.category.as_deref() {
+            builder
+                .push(" AND id IN (SELECT post_category.post_id FROM post_category INNER JOIN category ON category.id = post_category.category_id WHERE post_category.blog_id = ")
+                .push_bind(id)
+                .push(" AND category.slug = ")
+                .push_bind(category)
+                .push(")");
+        }
+
+        if let Some(tag) = self.tag.as_deref() {
+            builder
+                .push(" AND id IN (SELECT post_tag.post_id FROM post_tag INNER JOIN tag ON tag.id = post_tag.tag_id WHERE post_tag.blog_id = ")
+                .push_bind(id)
+                .push(" AND tag.slug = ")
+                .push_bind(tag)
+                .push(")");
+        }
+
+        if let Some(author) = self.author.as_deref() {
+            builder
+                .push(" AND (author_id IN (SELECT id FROM author WHERE blog_id = ")
+                .push_bind(id)
+                .push(" AND slug = ")
+                .push_bind(author)
+                .push(") OR id IN (SELECT post_author.post_id FROM post_author INNER JOIN author ON author.id = post_author.author_id WHERE post_author.blog_id = ")
+                .push_bind(id)
+                .push(" AND author.slug = ")
+                .push_bind(author)
+                .push("))");
+        }
+
+        if let Some(visible_at) = self.visible_at {
+            builder.push(" AND post_date <= ").push_bind(visible_at);
+        }
+
+        if let Some(from) = self.from {
+            builder.push(" AND post_date >= ").push_bind(from);
+        }
+
+        if let Some(to) = self.to {
+            builder.push(" AND post_date <= ").push_bind(to);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum PostSort {
+    PostDate,
+    CreatedAt,
+    UpdatedAt,
+    Title,
+}
+
+impl PostSort {
+    fn column(self) -> &'static str {
+        match self {
+            Self::PostDate => "post_date",
+            Self::CreatedAt => "created_at",
+            Self::UpdatedAt => "updated_at",
+            Self::Title => "title",
+        }
+    }
+}
+
+impl FromStr for PostSort {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "post_date" => Self::PostDate,
+            "created_at" => Self::CreatedAt,
+            "updated_at" => Self::UpdatedAt,
+            "title" => Self::Title,
+            _ => return Err(eyre::eyre!("Unknown sort key: {s}")),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
 #[derive(
     Debug, Clone, Copy, serde::Serialize, serde::Deserialize, IntoPrimitive, TryFromPrimitive,
 )]
@@ -135,6 +567,21 @@ impl PostModel {
 pub enum PostStatus {
     Draft = 0,
     Published = 1,
+    /// Published once `post_date` passes
+    Scheduled = 2,
+}
+
+impl PostStatus {
+    /// Published posts with a future date become scheduled, scheduled posts with a past date become published.
+    pub fn for_post_date(self, post_date: OffsetDateTime) -> Self {
+        let now = OffsetDateTime::now_utc();
+
+        match self {
+            Self::Published if post_date > now => Self::Scheduled,
+            Self::Scheduled if post_date <= now => Self::Published,
+            v => v,
+        }
+    }
 }
 
 impl FromRow<'_, ::sqlx::sqlite::SqliteRow> for PostStatus {
@@ -170,34 +617,139 @@ impl ::sqlx::Type<::sqlx::sqlite::Sqlite> for PostStatus {
     }
 }
 
+/// Longest generated slug. Longer values are cut at the last whole word that fits.
+pub const MAX_SLUG_LENGTH: usize = 60;
+
+/// Lowercase words joined by hyphens.
+///
+/// Accented letters are transliterated (`Crème brûlée` -> `creme-brulee`). Letters from other
+/// scripts are kept as they are, everything else separates words.
 pub fn slugify(value: &str) -> String {
-    // trim leading/trailing white space
-    let mut value = Regex::new(r#"^\s+|\s+$"#)
-        .unwrap()
-        .replace_all(value, "")
-        .to_string();
-
-    value = value.to_lowercase();
-
-    // remove any non-alphanumeric characters
-    value = Regex::new(r#"[^a-z0-9 -]"#)
-        .unwrap()
-        .replace(&value, "")
-        .to_string();
-    // replace spaces with hyphens
-    value = Regex::new(r#"\s+"#)
-        .unwrap()
-        .replace(&value, "-")
-        .to_string();
-    // remove consecutive hyphens
-    value = Regex::new(r#"-+"#)
-        .unwrap()
-        .replace(&value, "-")
-        .to_string();
-
-    if value.len() > 30 {
-        value = value.chars().take(30).collect();
-    }
-
-    value
+    let mut slug = String::new();
+
+    for c in value.nfc() {
+        let base = std::iter::once(c)
+            .nfkd()
+            .filter(|c| !is_combining_mark(*c))
+            .collect::<String>();
+
+        // Only accents on ASCII letters come off, as "が" or "한" would otherwise become other letters
+        let chars = if !base.is_empty() && base.is_ascii() {
+            base
+        } else {
+            c.to_string()
+        };
+
+        for c in chars.chars() {
+            if let Some(ascii) = transliterate(c) {
+                slug.push_str(ascii);
+            } else if c.is_alphanumeric() {
+                slug.extend(c.to_lowercase());
+            } else if matches!(c, '\'' | '\u{2019}') {
+                // Keeps "don't" as one word
+            } else if is_combining_mark(c) {
+                // Marks which don't compose with the letter before them, like the Devanagari virama
+                if slug.chars().last().is_some_and(|v| !v.is_ascii()) {
+                    slug.push(c);
+                }
+            } else if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        }
+    }
+
+    while slug.ends_with('-') {
+        slug.pop();
+    }
+
+    let slug = slug.nfc().collect::<String>();
+
+    truncate_slug(&slug, MAX_SLUG_LENGTH).to_string()
+}
+
+/// Shortens the slug to at most `max_chars`, cutting before the word which doesn't fit unless it's
+/// the only one.
+pub fn truncate_slug(slug: &str, max_chars: usize) -> &str {
+    let Some((cut, _)) = slug.char_indices().nth(max_chars) else {
+        return slug;
+    };
+
+    let cut = if slug[cut..].starts_with('-') {
+        cut
+    } else {
+        slug[..cut].rfind('-').unwrap_or(cut)
+    };
+
+    &slug[..cut]
+}
+
+/// Whether the value is already in the form `slugify` outputs.
+pub fn is_valid_slug(value: &str) -> bool {
+    !value.is_empty() && slugify(value) == value
+}
+
+/// Letters which don't decompose into an ASCII letter and an accent.
+fn transliterate(c: char) -> Option<&'static str> {
+    Some(match c {
+        'ß' | 'ẞ' => "ss",
+        'æ' | 'Æ' => "ae",
+        'œ' | 'Œ' => "oe",
+        'ø' | 'Ø' => "o",
+        'đ' | 'Đ' | 'ð' | 'Ð' => "d",
+        'ł' | 'Ł' => "l",
+        'þ' | 'Þ' => "th",
+        'ı' => "i",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slugify_transliterates_latin_letters() {
+        assert_eq!(slugify("Crème brûlée"), "creme-brulee");
+        assert_eq!(slugify("Straße & Smørrebrød"), "strasse-smorrebrod");
+        assert_eq!(slugify("  Don't   panic!  "), "dont-panic");
+        assert_eq!(slugify("Ｆｕｌｌ ｗｉｄｔｈ"), "full-width");
+        assert_eq!(slugify("?!"), "");
+    }
+
+    #[test]
+    fn slugify_keeps_other_scripts() {
+        assert_eq!(slugify("がっこう"), "がっこう");
+        assert_eq!(slugify("한국어 블로그"), "한국어-블로그");
+        assert_eq!(slugify("Привет, мир"), "привет-мир");
+        assert_eq!(slugify("नमस्ते"), "नमस्ते");
+
+        // Decomposed input comes out composed
+        assert_eq!(slugify("\u{304B}\u{3099}"), "が");
+        assert_eq!(slugify("e\u{301}te\u{301}"), "ete");
+    }
+
+    #[test]
+    fn slugify_cuts_between_words() {
+        let title = "word ".repeat(20);
+        let slug = slugify(&title);
+
+        assert!(slug.chars().count() <= MAX_SLUG_LENGTH);
+        assert!(slug.ends_with("word"));
+
+        let long = "a".repeat(MAX_SLUG_LENGTH + 10);
+        assert_eq!(slugify(&long).chars().count(), MAX_SLUG_LENGTH);
+    }
+
+    #[test]
+    fn valid_slugs() {
+        assert!(is_valid_slug("hello-world"));
+        assert!(is_valid_slug("한국어"));
+        assert!(is_valid_slug("がっこう"));
+
+        assert!(!is_valid_slug(""));
+        assert!(!is_valid_slug("Hello"));
+        assert!(!is_valid_slug("hello--world"));
+        assert!(!is_valid_slug("-hello"));
+        assert!(!is_valid_slug("crème"));
+    }
 }
@@ -0,0 +1,51 @@
+use eyre::Result;
+use serde::Serialize;
+use sqlx::{FromRow, SqliteConnection};
+
+use crate::{AuthorId, BlogId, PostId};
+
+/// Co-author of a post. The main author is `post.author_id`.
+#[derive(FromRow, Serialize)]
+pub struct PostAuthorModel {
+    pub blog_id: BlogId,
+
+    pub post_id: PostId,
+    pub author_id: AuthorId,
+
+    pub position: i32,
+}
+
+impl PostAuthorModel {
+    pub async fn insert(self, db: &mut SqliteConnection) -> Result<PostAuthorModel> {
+        sqlx::query(
+            "INSERT INTO post_author (blog_id, post_id, author_id, position) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(self.blog_id)
+        .bind(self.post_id)
+        .bind(self.author_id)
+        .bind(self.position)
+        .execute(db)
+        .await?;
+
+        Ok(PostAuthorModel {
+            blog_id: self.blog_id,
+            post_id: self.post_id,
+            author_id: self.author_id,
+            position: self.position,
+        })
+    }
+
+    pub async fn delete_by_post_id(
+        blog_id: BlogId,
+        post_id: PostId,
+        db: &mut SqliteConnection,
+    ) -> Result<u64> {
+        let res = sqlx::query("DELETE FROM post_author WHERE blog_id = $1 AND post_id = $2")
+            .bind(blog_id)
+            .bind(post_id)
+            .execute(db)
+            .await?;
+
+        Ok(res.rows_affected())
+    }
+}
@@ -29,4 +29,18 @@ impl PostCategoryModel {
             category_id: self.category_id,
         })
     }
+
+    pub async fn delete_by_post_id(
+        blog_id: BlogId,
+        post_id: PostId,
+        db: &mut SqliteConnection,
+    ) -> Result<u64> {
+        let res = sqlx::query("DELETE FROM post_category WHERE blog_id = $1 AND post_id = $2")
+            .bind(blog_id)
+            .bind(post_id)
+            .execute(db)
+            .await?;
+
+        Ok(res.rows_affected())
+    }
 }
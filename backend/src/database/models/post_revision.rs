@@ -0,0 +1,84 @@
+use eyre::Result;
+use serde::Serialize;
+use sqlx::{types::Json, FromRow, SqliteConnection};
+use time::OffsetDateTime;
+
+use crate::{BlogId, PostId, PostRevisionId};
+
+pub struct NewPostRevisionModel {
+    pub blog_id: BlogId,
+    pub post_id: PostId,
+
+    pub title: String,
+    pub content: serde_json::Value,
+
+    pub restored_from: Option<PostRevisionId>,
+}
+
+#[derive(FromRow, Serialize)]
+pub struct PostRevisionModel {
+    pub id: PostRevisionId,
+
+    pub blog_id: BlogId,
+    pub post_id: PostId,
+
+    pub title: String,
+    pub content: Json<serde_json::Value>,
+
+    pub restored_from: Option<PostRevisionId>,
+
+    pub created_at: OffsetDateTime,
+}
+
+impl NewPostRevisionModel {
+    pub async fn insert(self, db: &mut SqliteConnection) -> Result<PostRevisionModel> {
+        let now = OffsetDateTime::now_utc();
+
+        let resp = sqlx::query(
+            "INSERT INTO post_revision (blog_id, post_id, title, content, restored_from, created_at) VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(self.blog_id)
+        .bind(self.post_id)
+        .bind(&self.title)
+        .bind(Json(&self.content))
+        .bind(self.restored_from)
+        .bind(now)
+        .execute(db)
+        .await?;
+
+        Ok(PostRevisionModel {
+            id: PostRevisionId::from(resp.last_insert_rowid()),
+            blog_id: self.blog_id,
+            post_id: self.post_id,
+            title: self.title,
+            content: Json(self.content),
+            restored_from: self.restored_from,
+            created_at: now,
+        })
+    }
+}
+
+impl PostRevisionModel {
+    pub async fn find_one_by_id(
+        post_id: PostId,
+        id: PostRevisionId,
+        db: &mut SqliteConnection,
+    ) -> Result<Option<Self>> {
+        Ok(sqlx::query_as(
+            "SELECT id, blog_id, post_id, title, content, restored_from, created_at FROM post_revision WHERE post_id = $1 AND id = $2",
+        )
+        .bind(post_id)
+        .bind(id)
+        .fetch_optional(db)
+        .await?)
+    }
+
+    pub async fn find_by_post_id(post_id: PostId, db: &mut SqliteConnection) -> Result<Vec<Self>> {
+        Ok(sqlx::query_as(
+            "SELECT id, blog_id, post_id, title, content, restored_from, created_at FROM post_revision WHERE post_id = $1 ORDER BY id DESC",
+        )
+        .bind(post_id)
+        .fetch_all(db)
+        .await?)
+    }
+}
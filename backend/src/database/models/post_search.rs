@@ -0,0 +1,119 @@
+use eyre::Result;
+use serde::Serialize;
+use sqlx::{FromRow, SqliteConnection};
+use time::OffsetDateTime;
+
+use crate::{BlogId, PostId};
+
+use super::PostStatus;
+
+/// Wraps matched terms in highlights and snippets. Control characters are dropped from the indexed
+/// post text, which lets the caller escape the text before turning these into markup.
+pub const HIGHLIGHT_START: char = '\u{2}';
+pub const HIGHLIGHT_END: char = '\u{3}';
+
+/// Column weights for title, body, tags
+const RANK_WEIGHTS: &str = "10.0, 1.0, 5.0";
+
+#[derive(FromRow, Serialize)]
+pub struct PostSearchModel {
+    pub id: PostId,
+
+    pub title: String,
+    pub slug: Option<String>,
+    pub excerpt: String,
+    pub custom_excerpt: Option<String>,
+
+    pub post_date: OffsetDateTime,
+
+    pub title_highlight: String,
+    pub snippet: String,
+    pub rank: f64,
+}
+
+impl PostSearchModel {
+    /// Searches published, visible posts of the blog. `query` must already be an FTS5 query.
+    pub async fn search(
+        blog_id: BlogId,
+        query: &str,
+        offset: i64,
+        limit: i64,
+        db: &mut SqliteConnection,
+    ) -> Result<(Vec<Self>, i64)> {
+        let now = OffsetDateTime::now_utc();
+
+        let total = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM post_search INNER JOIN post ON post.id = post_search.rowid WHERE post_search MATCH $1 AND post.blog_id = $2 AND post.status = $3 AND post.post_date <= $4 AND post.deleted_at IS NULL",
+        )
+        .bind(query)
+        .bind(blog_id)
+        .bind(PostStatus::Published)
+        .bind(now)
+        .fetch_one(&mut *db)
+        .await?;
+
+        let items = sqlx::query_as(&format!(
+            "SELECT post.id, post.title, post.slug, post.excerpt, post.custom_excerpt, post.post_date, highlight(post_search, 0, $5, $6) AS title_highlight, snippet(post_search, 1, $5, $6, '…', 24) AS snippet, bm25(post_search, {RANK_WEIGHTS}) AS rank FROM post_search INNER JOIN post ON post.id = post_search.rowid WHERE post_search MATCH $1 AND post.blog_id = $2 AND post.status = $3 AND post.post_date <= $4 AND post.deleted_at IS NULL ORDER BY rank LIMIT $7 OFFSET $8",
+        ))
+        .bind(query)
+        .bind(blog_id)
+        .bind(PostStatus::Published)
+        .bind(now)
+        .bind(HIGHLIGHT_START.to_string())
+        .bind(HIGHLIGHT_END.to_string())
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(db)
+        .await?;
+
+        Ok((items, total))
+    }
+
+    /// Rebuilds the search index for every post of the blog.
+    pub async fn reindex(blog_id: BlogId, db: &mut SqliteConnection) -> Result<u64> {
+        sqlx::query(
+            "DELETE FROM post_search WHERE rowid IN (SELECT id FROM post WHERE blog_id = $1)",
+        )
+        .bind(blog_id)
+        .execute(&mut *db)
+        .await?;
+
+        let res = sqlx::query(
+            "INSERT INTO post_search (rowid, title, body, tags) SELECT post.id, post.title, post.plain_text, (SELECT group_concat(tag.name, ' ') FROM post_tag INNER JOIN tag ON tag.id = post_tag.tag_id WHERE post_tag.post_id = post.id) FROM post WHERE post.blog_id = $1",
+        )
+        .bind(blog_id)
+        .execute(db)
+        .await?;
+
+        Ok(res.rows_affected())
+    }
+}
+
+/// Turns user input into an FTS5 query.
+///
+/// Every word is quoted so FTS5 syntax in the input is searched for literally. The last word also
+/// matches as a prefix for search-as-you-type.
+pub fn to_match_query(value: &str) -> Option<String> {
+    let words = value
+        .split_whitespace()
+        .map(|word| word.replace('"', ""))
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>();
+
+    let last = words.len().checked_sub(1)?;
+
+    Some(
+        words
+            .iter()
+            .enumerate()
+            .map(|(i, word)| {
+                if i == last {
+                    format!("\"{word}\"*")
+                } else {
+                    format!("\"{word}\"")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
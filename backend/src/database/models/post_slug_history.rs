@@ -0,0 +1,94 @@
+use eyre::Result;
+use serde::Serialize;
+use sqlx::{FromRow, SqliteConnection};
+use time::OffsetDateTime;
+
+use crate::{BlogId, PostId};
+
+/// A slug the post used before. Kept until another post takes the slug.
+#[derive(FromRow, Serialize)]
+pub struct PostSlugHistoryModel {
+    pub blog_id: BlogId,
+
+    pub slug: String,
+    pub post_id: PostId,
+
+    pub created_at: OffsetDateTime,
+}
+
+impl PostSlugHistoryModel {
+    /// Points the slug at the post, replacing whichever post had it before.
+    pub async fn record(
+        blog_id: BlogId,
+        post_id: PostId,
+        slug: &str,
+        db: &mut SqliteConnection,
+    ) -> Result<u64> {
+        let res = sqlx::query(
+            "INSERT INTO post_slug_history (blog_id, slug, post_id, created_at) VALUES ($1, $2, $3, $4) ON CONFLICT (blog_id, slug) DO UPDATE SET post_id = excluded.post_id, created_at = excluded.created_at",
+        )
+        .bind(blog_id)
+        .bind(slug)
+        .bind(post_id)
+        .bind(OffsetDateTime::now_utc())
+        .execute(db)
+        .await?;
+
+        Ok(res.rows_affected())
+    }
+
+    pub async fn find_one_by_slug(
+        blog_id: BlogId,
+        slug: &str,
+        db: &mut SqliteConnection,
+    ) -> Result<Option<Self>> {
+        Ok(sqlx::query_as(
+            "SELECT blog_id, slug, post_id, created_at FROM post_slug_history WHERE blog_id = $1 AND slug = $2",
+        )
+        .bind(blog_id)
+        .bind(slug)
+        .fetch_optional(db)
+        .await?)
+    }
+
+    /// Newest first
+    pub async fn find_by_post_id(id: PostId, db: &mut SqliteConnection) -> Result<Vec<Self>> {
+        Ok(sqlx::query_as(
+            "SELECT blog_id, slug, post_id, created_at FROM post_slug_history WHERE post_id = $1 ORDER BY created_at DESC",
+        )
+        .bind(id)
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Previous slugs which are `slug` or `slug-{suffix}`.
+    pub async fn find_slugs_like(
+        blog_id: BlogId,
+        slug: &str,
+        db: &mut SqliteConnection,
+    ) -> Result<Vec<String>> {
+        Ok(sqlx::query_scalar(
+            "SELECT slug FROM post_slug_history WHERE blog_id = $1 AND (slug = $2 OR slug LIKE $3)",
+        )
+        .bind(blog_id)
+        .bind(slug)
+        .bind(format!("{slug}-%"))
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// The slug now belongs to a post again.
+    pub async fn delete_by_slug(
+        blog_id: BlogId,
+        slug: &str,
+        db: &mut SqliteConnection,
+    ) -> Result<u64> {
+        let res = sqlx::query("DELETE FROM post_slug_history WHERE blog_id = $1 AND slug = $2")
+            .bind(blog_id)
+            .bind(slug)
+            .execute(db)
+            .await?;
+
+        Ok(res.rows_affected())
+    }
+}
@@ -0,0 +1,137 @@
+use eyre::Result;
+use serde::Serialize;
+use sqlx::{FromRow, QueryBuilder, SqliteConnection};
+use time::OffsetDateTime;
+
+use crate::BlogId;
+
+#[derive(FromRow, Serialize)]
+pub struct SpamBlocklistModel {
+    pub blog_id: BlogId,
+
+    pub word: String,
+
+    pub created_at: OffsetDateTime,
+}
+
+impl SpamBlocklistModel {
+    pub async fn find_by_blog_id(id: BlogId, db: &mut SqliteConnection) -> Result<Vec<Self>> {
+        Ok(sqlx::query_as(
+            "SELECT blog_id, word, created_at FROM spam_blocklist WHERE blog_id = $1 ORDER BY word",
+        )
+        .bind(id)
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Replaces the blocklist of the blog.
+    pub async fn replace(id: BlogId, words: &[String], db: &mut SqliteConnection) -> Result<()> {
+        sqlx::query("DELETE FROM spam_blocklist WHERE blog_id = $1")
+            .bind(id)
+            .execute(&mut *db)
+            .await?;
+
+        let now = OffsetDateTime::now_utc();
+
+        for word in words {
+            sqlx::query(
+                "INSERT OR IGNORE INTO spam_blocklist (blog_id, word, created_at) VALUES ($1, $2, $3)",
+            )
+            .bind(id)
+            .bind(word)
+            .bind(now)
+            .execute(&mut *db)
+            .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(FromRow)]
+pub struct SpamTokenModel {
+    pub token: String,
+
+    pub spam_count: i64,
+    pub ham_count: i64,
+}
+
+impl SpamTokenModel {
+    pub async fn find_by_tokens(
+        id: BlogId,
+        tokens: &[String],
+        db: &mut SqliteConnection,
+    ) -> Result<Vec<Self>> {
+        if tokens.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut builder = QueryBuilder::new(
+            "SELECT token, spam_count, ham_count FROM spam_token WHERE blog_id = ",
+        );
+
+        builder.push_bind(id).push(" AND token IN (");
+
+        let mut separated = builder.separated(", ");
+
+        for token in tokens {
+            separated.push_bind(token);
+        }
+
+        builder.push(")");
+
+        Ok(builder.build_query_as().fetch_all(db).await?)
+    }
+
+    /// Adds (or with a negative `amount`, removes) the tokens of a comment to the spam or ham counts.
+    pub async fn train(
+        id: BlogId,
+        tokens: &[String],
+        is_spam: bool,
+        amount: i64,
+        db: &mut SqliteConnection,
+    ) -> Result<()> {
+        let (spam, ham) = if is_spam { (amount, 0) } else { (0, amount) };
+
+        for token in tokens {
+            sqlx::query(
+                "INSERT INTO spam_token (blog_id, token, spam_count, ham_count) VALUES ($1, $2, max($3, 0), max($4, 0)) ON CONFLICT (blog_id, token) DO UPDATE SET spam_count = max(spam_count + $3, 0), ham_count = max(ham_count + $4, 0)",
+            )
+            .bind(id)
+            .bind(token)
+            .bind(spam)
+            .bind(ham)
+            .execute(&mut *db)
+            .await?;
+        }
+
+        sqlx::query(
+            "INSERT INTO spam_corpus (blog_id, spam_count, ham_count) VALUES ($1, max($2, 0), max($3, 0)) ON CONFLICT (blog_id) DO UPDATE SET spam_count = max(spam_count + $2, 0), ham_count = max(ham_count + $3, 0)",
+        )
+        .bind(id)
+        .bind(spam)
+        .bind(ham)
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(FromRow, Default)]
+pub struct SpamCorpusModel {
+    pub spam_count: i64,
+    pub ham_count: i64,
+}
+
+impl SpamCorpusModel {
+    pub async fn find_one_by_blog_id(id: BlogId, db: &mut SqliteConnection) -> Result<Self> {
+        let found =
+            sqlx::query_as("SELECT spam_count, ham_count FROM spam_corpus WHERE blog_id = $1")
+                .bind(id)
+                .fetch_optional(db)
+                .await?;
+
+        Ok(found.unwrap_or_default())
+    }
+}
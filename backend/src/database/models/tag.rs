@@ -1,45 +1,265 @@
 use eyre::Result;
 use serde::Serialize;
 use sqlx::{FromRow, SqliteConnection};
-use uuid::Uuid;
+use time::OffsetDateTime;
 
-use crate::TagId;
+use crate::{BlogId, PostId, TagId};
 
 pub struct NewTagModel {
+    pub blog_id: BlogId,
+
     pub name: String,
+    pub slug: String,
 }
 
 #[derive(FromRow, Serialize)]
 pub struct TagModel {
     pub id: TagId,
+
+    pub blog_id: BlogId,
+
     pub name: String,
+    pub slug: String,
+
+    pub created_at: OffsetDateTime,
+    pub updated_at: OffsetDateTime,
+}
+
+/// Tag along with the number of (non-deleted) posts using it.
+#[derive(FromRow, Serialize)]
+pub struct TagUsageModel {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub tag: TagModel,
+
+    pub post_count: i64,
 }
 
 impl NewTagModel {
     pub async fn insert(self, db: &mut SqliteConnection) -> Result<TagModel> {
-        let resp = sqlx::query("INSERT INTO tag (name) VALUES ($1)")
-            .bind(&self.name)
+        let now = OffsetDateTime::now_utc();
+
+        let resp = sqlx::query(
+            "INSERT INTO tag (blog_id, name, slug, created_at, updated_at) VALUES ($1, $2, $3, $4, $4)",
+        )
+        .bind(self.blog_id)
+        .bind(&self.name)
+        .bind(&self.slug)
+        .bind(now)
+        .execute(&mut *db)
+        .await?;
+
+        let id = TagId::from(resp.last_insert_rowid());
+
+        // The slug now belongs to this tag
+        sqlx::query("DELETE FROM tag_slug_alias WHERE blog_id = $1 AND slug = $2")
+            .bind(self.blog_id)
+            .bind(&self.slug)
             .execute(db)
             .await?;
 
         Ok(TagModel {
-            id: TagId::from(resp.last_insert_rowid()),
+            id,
+            blog_id: self.blog_id,
             name: self.name,
+            slug: self.slug,
+            created_at: now,
+            updated_at: now,
         })
     }
 }
 
 impl TagModel {
-    pub async fn find_one_by_guid(guid: Uuid, db: &mut SqliteConnection) -> Result<Option<Self>> {
-        Ok(sqlx::query_as("SELECT id, name FROM tag WHERE guid = $1")
-            .bind(guid)
-            .fetch_optional(db)
-            .await?)
+    /// Keeps the previous slug as an alias when it changes.
+    pub async fn rename(
+        &mut self,
+        name: String,
+        slug: String,
+        db: &mut SqliteConnection,
+    ) -> Result<u64> {
+        if !self.slug.eq_ignore_ascii_case(&slug) {
+            sqlx::query("DELETE FROM tag_slug_alias WHERE blog_id = $1 AND slug = $2")
+                .bind(self.blog_id)
+                .bind(&slug)
+                .execute(&mut *db)
+                .await?;
+
+            sqlx::query(
+                "INSERT OR REPLACE INTO tag_slug_alias (blog_id, slug, tag_id) VALUES ($1, $2, $3)",
+            )
+            .bind(self.blog_id)
+            .bind(&self.slug)
+            .bind(self.id)
+            .execute(&mut *db)
+            .await?;
+        }
+
+        self.name = name;
+        self.slug = slug;
+        self.updated_at = OffsetDateTime::now_utc();
+
+        let res = sqlx::query(
+            "UPDATE tag SET name = $2, slug = $3, updated_at = $4 WHERE id = $1 AND blog_id = $5",
+        )
+        .bind(self.id)
+        .bind(&self.name)
+        .bind(&self.slug)
+        .bind(self.updated_at)
+        .bind(self.blog_id)
+        .execute(db)
+        .await?;
+
+        Ok(res.rows_affected())
+    }
+
+    /// Moves every post and slug of the `source` tag over to `target`, then deletes `source`.
+    pub async fn merge(
+        blog_id: BlogId,
+        source: TagId,
+        target: TagId,
+        db: &mut SqliteConnection,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT OR IGNORE INTO post_tag (blog_id, post_id, tag_id) SELECT blog_id, post_id, $3 FROM post_tag WHERE blog_id = $1 AND tag_id = $2",
+        )
+        .bind(blog_id)
+        .bind(source)
+        .bind(target)
+        .execute(&mut *db)
+        .await?;
+
+        sqlx::query("DELETE FROM post_tag WHERE blog_id = $1 AND tag_id = $2")
+            .bind(blog_id)
+            .bind(source)
+            .execute(&mut *db)
+            .await?;
+
+        sqlx::query("UPDATE tag_slug_alias SET tag_id = $3 WHERE blog_id = $1 AND tag_id = $2")
+            .bind(blog_id)
+            .bind(source)
+            .bind(target)
+            .execute(&mut *db)
+            .await?;
+
+        sqlx::query(
+            "INSERT OR REPLACE INTO tag_slug_alias (blog_id, slug, tag_id) SELECT blog_id, slug, $3 FROM tag WHERE blog_id = $1 AND id = $2",
+        )
+        .bind(blog_id)
+        .bind(source)
+        .bind(target)
+        .execute(&mut *db)
+        .await?;
+
+        Self::delete(blog_id, source, db).await?;
+
+        Ok(())
+    }
+
+    pub async fn find_one_by_id(
+        blog_id: BlogId,
+        id: TagId,
+        db: &mut SqliteConnection,
+    ) -> Result<Option<Self>> {
+        Ok(sqlx::query_as(
+            "SELECT id, blog_id, name, slug, created_at, updated_at FROM tag WHERE blog_id = $1 AND id = $2",
+        )
+        .bind(blog_id)
+        .bind(id)
+        .fetch_optional(db)
+        .await?)
+    }
+
+    pub async fn find_one_by_slug(
+        blog_id: BlogId,
+        slug: &str,
+        db: &mut SqliteConnection,
+    ) -> Result<Option<Self>> {
+        Ok(sqlx::query_as(
+            "SELECT id, blog_id, name, slug, created_at, updated_at FROM tag WHERE blog_id = $1 AND slug = $2",
+        )
+        .bind(blog_id)
+        .bind(slug)
+        .fetch_optional(db)
+        .await?)
+    }
+
+    /// Like [`Self::find_one_by_slug`], falling back to the previous slugs of renamed and merged tags.
+    pub async fn find_one_by_slug_or_alias(
+        blog_id: BlogId,
+        slug: &str,
+        db: &mut SqliteConnection,
+    ) -> Result<Option<Self>> {
+        if let Some(tag) = Self::find_one_by_slug(blog_id, slug, &mut *db).await? {
+            return Ok(Some(tag));
+        }
+
+        Ok(sqlx::query_as(
+            "SELECT tag.id, tag.blog_id, tag.name, tag.slug, tag.created_at, tag.updated_at FROM tag INNER JOIN tag_slug_alias ON tag_slug_alias.tag_id = tag.id WHERE tag_slug_alias.blog_id = $1 AND tag_slug_alias.slug = $2",
+        )
+        .bind(blog_id)
+        .bind(slug)
+        .fetch_optional(db)
+        .await?)
     }
 
-    pub async fn find_all(db: &mut SqliteConnection) -> Result<Vec<Self>> {
-        Ok(sqlx::query_as("SELECT id, name FROM tag")
-            .fetch_all(db)
-            .await?)
+    pub async fn find_by_blog_id_with_usage(
+        id: BlogId,
+        db: &mut SqliteConnection,
+    ) -> Result<Vec<TagUsageModel>> {
+        Ok(sqlx::query_as(
+            "SELECT tag.id, tag.blog_id, tag.name, tag.slug, tag.created_at, tag.updated_at, COUNT(post.id) AS post_count FROM tag LEFT JOIN post_tag ON post_tag.tag_id = tag.id LEFT JOIN post ON post.id = post_tag.post_id AND post.deleted_at IS NULL WHERE tag.blog_id = $1 GROUP BY tag.id ORDER BY tag.name",
+        )
+        .bind(id)
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Tags whose name or slug starts with `prefix`, most used first.
+    pub async fn autocomplete(
+        id: BlogId,
+        prefix: &str,
+        limit: i64,
+        db: &mut SqliteConnection,
+    ) -> Result<Vec<TagUsageModel>> {
+        let pattern = format!(
+            "{}%",
+            prefix
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_")
+        );
+
+        Ok(sqlx::query_as(
+            "SELECT tag.id, tag.blog_id, tag.name, tag.slug, tag.created_at, tag.updated_at, COUNT(post.id) AS post_count FROM tag LEFT JOIN post_tag ON post_tag.tag_id = tag.id LEFT JOIN post ON post.id = post_tag.post_id AND post.deleted_at IS NULL WHERE tag.blog_id = $1 AND (tag.name LIKE $2 ESCAPE '\\' OR tag.slug LIKE $2 ESCAPE '\\') GROUP BY tag.id ORDER BY post_count DESC, tag.name LIMIT $3",
+        )
+        .bind(id)
+        .bind(pattern)
+        .bind(limit)
+        .fetch_all(db)
+        .await?)
+    }
+
+    pub async fn find_by_post_id(
+        blog_id: BlogId,
+        post_id: PostId,
+        db: &mut SqliteConnection,
+    ) -> Result<Vec<Self>> {
+        Ok(sqlx::query_as(
+            "SELECT tag.id, tag.blog_id, tag.name, tag.slug, tag.created_at, tag.updated_at FROM tag INNER JOIN post_tag ON post_tag.tag_id = tag.id WHERE post_tag.blog_id = $1 AND post_tag.post_id = $2 AND tag.blog_id = $1 ORDER BY tag.name",
+        )
+        .bind(blog_id)
+        .bind(post_id)
+        .fetch_all(db)
+        .await?)
+    }
+
+    pub async fn delete(blog_id: BlogId, id: TagId, db: &mut SqliteConnection) -> Result<u64> {
+        let res = sqlx::query("DELETE FROM tag WHERE blog_id = $1 AND id = $2")
+            .bind(blog_id)
+            .bind(id)
+            .execute(db)
+            .await?;
+
+        Ok(res.rows_affected())
     }
 }
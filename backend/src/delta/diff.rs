@@ -0,0 +1,88 @@
+use serde::Serialize;
+
+use super::{Delta, Line};
+
+/// Past this many line comparisons the diff falls back to replacing the whole document.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Equal,
+    Insert,
+    Delete,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LineChange {
+    pub kind: ChangeKind,
+    pub text: String,
+    pub line: Line,
+}
+
+/// Line based diff between two Deltas.
+///
+/// Lines are compared along with their inline and block formatting, so a line which was only bolded
+/// or turned into a header shows up as a delete + insert.
+pub fn diff(old: &Delta, new: &Delta) -> Vec<LineChange> {
+    let old = old.lines();
+    let new = new.lines();
+
+    if old.len().saturating_mul(new.len()) > MAX_DIFF_CELLS {
+        return old
+            .into_iter()
+            .map(|line| change(ChangeKind::Delete, line))
+            .chain(new.into_iter().map(|line| change(ChangeKind::Insert, line)))
+            .collect();
+    }
+
+    // Longest common subsequence lengths of old[i..] and new[j..]
+    let width = new.len() + 1;
+    let mut table = vec![0u32; (old.len() + 1) * width];
+
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            table[i * width + j] = if old[i] == new[j] {
+                table[(i + 1) * width + j + 1] + 1
+            } else {
+                table[(i + 1) * width + j].max(table[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut changes = Vec::with_capacity(old.len().max(new.len()));
+
+    let mut old = old.into_iter().enumerate().peekable();
+    let mut new = new.into_iter().enumerate().peekable();
+
+    loop {
+        match (old.peek(), new.peek()) {
+            (Some((_, a)), Some((_, b))) if a == b => {
+                new.next();
+                changes.push(change(ChangeKind::Equal, old.next().unwrap().1));
+            }
+
+            (Some((i, _)), Some((j, _))) => {
+                if table[(i + 1) * width + j] >= table[i * width + j + 1] {
+                    changes.push(change(ChangeKind::Delete, old.next().unwrap().1));
+                } else {
+                    changes.push(change(ChangeKind::Insert, new.next().unwrap().1));
+                }
+            }
+
+            (Some(_), None) => changes.push(change(ChangeKind::Delete, old.next().unwrap().1)),
+            (None, Some(_)) => changes.push(change(ChangeKind::Insert, new.next().unwrap().1)),
+            (None, None) => break,
+        }
+    }
+
+    changes
+}
+
+fn change(kind: ChangeKind, line: Line) -> LineChange {
+    LineChange {
+        kind,
+        text: line.text(),
+        line,
+    }
+}
@@ -0,0 +1,539 @@
+use std::fmt::Write;
+
+use serde_json::Value;
+
+use super::{Attributes, Delta, Insert, Line, Segment};
+
+/// Deepest list nesting rendered - anything further is flattened onto this level.
+const MAX_INDENT: usize = 8;
+
+const LINK_SCHEMES: &[&str] = &["http", "https", "mailto", "tel"];
+const IMAGE_SCHEMES: &[&str] = &["http", "https"];
+/// Hosts whose embeddable players may be shown in an `<iframe>`.
+const VIDEO_HOSTS: &[&str] = &[
+    "www.youtube.com",
+    "youtube.com",
+    "www.youtube-nocookie.com",
+    "youtube-nocookie.com",
+    "player.vimeo.com",
+    "www.dailymotion.com",
+    "player.twitch.tv",
+];
+const IMAGE_DATA_TYPES: &[&str] = &[
+    "data:image/png;base64,",
+    "data:image/jpeg;base64,",
+    "data:image/gif;base64,",
+    "data:image/webp;base64,",
+];
+
+/// Render a Delta document into HTML.
+///
+/// Only known formats are emitted and every attribute value is escaped or validated, so the output is
+/// safe to embed as is. Unknown formats and embeds are dropped.
+pub fn render(delta: &Delta) -> String {
+    let mut out = String::new();
+
+    // Tag of each currently open list, indexed by indent. Every open list also has an open `<li>`.
+    let mut lists: Vec<&'static str> = Vec::new();
+    let mut in_code_block = false;
+
+    for line in delta.lines() {
+        let attrs = &line.attributes;
+
+        let is_code_block = attrs.contains_key("code-block");
+
+        if in_code_block && !is_code_block {
+            out.push_str("</code></pre>");
+            in_code_block = false;
+        }
+
+        if let Some((tag, checked)) = list_format(attrs) {
+            let indent = attrs
+                .get("indent")
+                .and_then(Value::as_u64)
+                .map_or(0, |v| (v as usize).min(MAX_INDENT));
+
+            while lists.len() > indent + 1 {
+                close_list(&mut out, lists.pop().unwrap());
+            }
+
+            if lists.len() == indent + 1 && lists[indent] != tag {
+                close_list(&mut out, lists.pop().unwrap());
+            }
+
+            if lists.len() == indent + 1 {
+                out.push_str("</li>");
+            } else {
+                while lists.len() < indent + 1 {
+                    lists.push(tag);
+
+                    // Skipped levels get an empty item to keep the nesting valid
+                    if lists.len() < indent + 1 {
+                        let _ = write!(out, "<{tag}><li>");
+                    } else {
+                        let _ = write!(out, "<{tag}>");
+                    }
+                }
+            }
+
+            match checked {
+                Some(checked) => {
+                    let _ = write!(out, r#"<li data-checked="{checked}">"#);
+                }
+                None => out.push_str("<li>"),
+            }
+
+            render_segments(&mut out, &line);
+
+            continue;
+        }
+
+        while let Some(tag) = lists.pop() {
+            close_list(&mut out, tag);
+        }
+
+        if is_code_block {
+            if in_code_block {
+                out.push('\n');
+            } else {
+                out.push_str("<pre><code>");
+                in_code_block = true;
+            }
+
+            // Code is displayed verbatim
+            escape_into(&mut out, &line.text());
+
+            continue;
+        }
+
+        let tag = match attrs.get("header").and_then(Value::as_u64) {
+            Some(level @ 1..=6) => ["h1", "h2", "h3", "h4", "h5", "h6"][level as usize - 1],
+            _ if attrs.contains_key("blockquote") => "blockquote",
+            _ => "p",
+        };
+
+        match attrs.get("align").and_then(Value::as_str) {
+            Some(align @ ("center" | "right" | "justify")) => {
+                let _ = write!(out, r#"<{tag} class="ql-align-{align}">"#);
+            }
+            _ => {
+                let _ = write!(out, "<{tag}>");
+            }
+        }
+
+        if line.segments.is_empty() {
+            out.push_str("<br>");
+        } else {
+            render_segments(&mut out, &line);
+        }
+
+        let _ = write!(out, "</{tag}>");
+    }
+
+    if in_code_block {
+        out.push_str("</code></pre>");
+    }
+
+    while let Some(tag) = lists.pop() {
+        close_list(&mut out, tag);
+    }
+
+    out
+}
+
+fn list_format(attrs: &Attributes) -> Option<(&'static str, Option<bool>)> {
+    match attrs.get("list").and_then(Value::as_str)? {
+        "ordered" => Some(("ol", None)),
+        "bullet" => Some(("ul", None)),
+        "checked" => Some(("ul", Some(true))),
+        "unchecked" => Some(("ul", Some(false))),
+        _ => None,
+    }
+}
+
+fn close_list(out: &mut String, tag: &str) {
+    let _ = write!(out, "</li></{tag}>");
+}
+
+fn render_segments(out: &mut String, line: &Line) {
+    for segment in &line.segments {
+        render_segment(out, segment);
+    }
+}
+
+fn render_segment(out: &mut String, segment: &Segment) {
+    let attrs = &segment.attributes;
+
+    let link = attrs
+        .get("link")
+        .and_then(Value::as_str)
+        .and_then(|url| sanitize_url(url, LINK_SCHEMES));
+
+    if let Some(link) = &link {
+        out.push_str(r#"<a href=""#);
+        escape_into(out, link);
+        out.push_str(r#"" rel="noopener noreferrer" target="_blank">"#);
+    }
+
+    let mut closing = Vec::new();
+
+    for (format, tag) in [
+        ("bold", "strong"),
+        ("italic", "em"),
+        ("underline", "u"),
+        ("strike", "s"),
+        ("code", "code"),
+    ] {
+        if attrs
+            .get(format)
+            .and_then(Value::as_bool)
+            .unwrap_or_default()
+        {
+            let _ = write!(out, "<{tag}>");
+            closing.push(tag);
+        }
+    }
+
+    match attrs.get("script").and_then(Value::as_str) {
+        Some("sub") => {
+            out.push_str("<sub>");
+            closing.push("sub");
+        }
+        Some("super") => {
+            out.push_str("<sup>");
+            closing.push("sup");
+        }
+        _ => (),
+    }
+
+    match &segment.insert {
+        Insert::Text(text) => escape_into(out, text),
+        Insert::Embed(embed) => render_embed(out, embed, attrs),
+    }
+
+    for tag in closing.into_iter().rev() {
+        let _ = write!(out, "</{tag}>");
+    }
+
+    if link.is_some() {
+        out.push_str("</a>");
+    }
+}
+
+fn render_embed(out: &mut String, embed: &Attributes, attrs: &Attributes) {
+    if let Some(src) = embed
+        .get("image")
+        .and_then(Value::as_str)
+        .and_then(sanitize_image_url)
+    {
+        out.push_str(r#"<img src=""#);
+        escape_into(out, &src);
+        out.push('"');
+
+        if let Some(alt) = attrs.get("alt").and_then(Value::as_str) {
+            out.push_str(r#" alt=""#);
+            escape_into(out, alt);
+            out.push('"');
+        }
+
+        for dimension in ["width", "height"] {
+            if let Some(value) = attrs
+                .get(dimension)
+                .and_then(Value::as_str)
+                .filter(|v| !v.is_empty() && v.chars().all(|c| c.is_ascii_digit()))
+            {
+                let _ = write!(out, r#" {dimension}="{value}""#);
+            }
+        }
+
+        out.push('>');
+    } else if let Some(src) = embed
+        .get("video")
+        .and_then(Value::as_str)
+        .and_then(sanitize_video_url)
+    {
+        out.push_str(r#"<iframe class="ql-video" src=""#);
+        escape_into(out, &src);
+        out.push_str(r#"" frameborder="0" allowfullscreen="true"></iframe>"#);
+    }
+}
+
+/// Returns the URL if it's relative or uses one of the allowed schemes.
+fn sanitize_url(url: &str, schemes: &[&str]) -> Option<String> {
+    let url = url.trim();
+
+    if url.is_empty() {
+        return None;
+    }
+
+    // Browsers ignore control characters and whitespace inside the scheme. eg. "java\tscript:"
+    let compact = url
+        .chars()
+        .filter(|c| !c.is_ascii_control() && !c.is_whitespace())
+        .collect::<String>();
+
+    match compact.find([':', '/', '?', '#']) {
+        Some(pos) if compact.as_bytes()[pos] == b':' => {
+            let scheme = compact[..pos].to_ascii_lowercase();
+
+            if schemes.contains(&scheme.as_str()) {
+                Some(url.to_string())
+            } else {
+                None
+            }
+        }
+
+        _ => Some(url.to_string()),
+    }
+}
+
+fn sanitize_image_url(url: &str) -> Option<String> {
+    let lower = url.trim().to_ascii_lowercase();
+
+    if lower.starts_with("data:") {
+        IMAGE_DATA_TYPES
+            .iter()
+            .any(|prefix| lower.starts_with(prefix))
+            .then(|| url.trim().to_string())
+    } else {
+        sanitize_url(url, IMAGE_SCHEMES)
+    }
+}
+
+/// Only https players from [`VIDEO_HOSTS`] can be embedded.
+fn sanitize_video_url(url: &str) -> Option<String> {
+    let url = url.trim();
+
+    let scheme = url.get(..8)?;
+    if !scheme.eq_ignore_ascii_case("https://") {
+        return None;
+    }
+
+    let rest = &url[8..];
+    let host = &rest[..rest.find(['/', '?', '#']).unwrap_or(rest.len())];
+
+    // Anything beyond a plain hostname (userinfo, ports, backslashes) is refused
+    if !host
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+    {
+        return None;
+    }
+
+    VIDEO_HOSTS
+        .iter()
+        .any(|allowed| host.eq_ignore_ascii_case(allowed))
+        .then(|| url.to_string())
+}
+
+pub fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+
+    escape_into(&mut out, value);
+
+    out
+}
+
+fn escape_into(out: &mut String, value: &str) {
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            // Not allowed in HTML, nor XML 1.0 for feeds
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => (),
+            c => out.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn render_ops(ops: Value) -> String {
+        render(&Delta::from_value(&json!({ "ops": ops })))
+    }
+
+    #[test]
+    fn rejects_unsafe_schemes() {
+        for url in [
+            "javascript:alert(1)",
+            "JavaScript:alert(1)",
+            "  javascript:alert(1)",
+            "java\tscript:alert(1)",
+            "java\nscript:alert(1)",
+            "\u{1}javascript:alert(1)",
+            "vbscript:msgbox(1)",
+            "data:text/html,<script>alert(1)</script>",
+            "DATA:text/html;base64,PHNjcmlwdD4=",
+        ] {
+            assert_eq!(sanitize_url(url, LINK_SCHEMES), None, "{url:?}");
+            assert_eq!(sanitize_image_url(url), None, "{url:?}");
+        }
+
+        assert_eq!(sanitize_url("", LINK_SCHEMES), None);
+        assert_eq!(
+            sanitize_image_url("data:image/svg+xml;base64,PHN2Zz4="),
+            None
+        );
+    }
+
+    #[test]
+    fn keeps_allowed_urls() {
+        for url in [
+            "https://example.com/a?b=c#d",
+            "HTTP://example.com",
+            "mailto:someone@example.com",
+            "/relative/path:with-colon",
+            "?query=a:b",
+            "#anchor",
+        ] {
+            assert_eq!(sanitize_url(url, LINK_SCHEMES).as_deref(), Some(url));
+        }
+
+        assert_eq!(
+            sanitize_url(" https://example.com ", LINK_SCHEMES).as_deref(),
+            Some("https://example.com")
+        );
+        assert_eq!(
+            sanitize_url("mailto:someone@example.com", IMAGE_SCHEMES),
+            None
+        );
+        assert_eq!(
+            sanitize_image_url("data:image/png;base64,AAAA").as_deref(),
+            Some("data:image/png;base64,AAAA")
+        );
+    }
+
+    #[test]
+    fn embeds_only_known_video_hosts() {
+        for url in [
+            "https://www.youtube.com/embed/abc",
+            "HTTPS://player.vimeo.com/video/1?autoplay=0",
+            " https://www.youtube-nocookie.com/embed/abc ",
+        ] {
+            assert_eq!(sanitize_video_url(url).as_deref(), Some(url.trim()));
+        }
+
+        for url in [
+            "https://example.com/embed/abc",
+            "http://www.youtube.com/embed/abc",
+            "//www.youtube.com/embed/abc",
+            "/embed/abc",
+            "https://www.youtube.com.example.com/embed",
+            "https://www.youtube.com@example.com/embed",
+            "https://example.com\\@www.youtube.com/embed",
+            "https://www.youtube.com:8443/embed",
+            "javascript:alert(1)",
+        ] {
+            assert_eq!(sanitize_video_url(url), None, "{url:?}");
+        }
+
+        assert_eq!(
+            render_ops(json!([
+                { "insert": { "video": "https://player.vimeo.com/video/1" } },
+                { "insert": { "video": "https://example.com/video/1" } },
+                { "insert": "\n" },
+            ])),
+            r#"<p><iframe class="ql-video" src="https://player.vimeo.com/video/1" frameborder="0" allowfullscreen="true"></iframe></p>"#
+        );
+    }
+
+    #[test]
+    fn escapes_text_and_attributes() {
+        assert_eq!(
+            escape("<a href=\"x\">'Tom' & Jerry</a>\u{0}\n"),
+            "&lt;a href=&quot;x&quot;&gt;&#39;Tom&#39; &amp; Jerry&lt;/a&gt;\n"
+        );
+
+        assert_eq!(
+            render_ops(json!([
+                { "insert": "<b>hi</b>", "attributes": { "link": "https://example.com/?a=1&b=\"2\"" } },
+                { "insert": { "image": "https://example.com/x.png" }, "attributes": { "alt": "\"><script>", "width": "10\" onload=\"x" } },
+                { "insert": "\n" },
+            ])),
+            concat!(
+                r#"<p><a href="https://example.com/?a=1&amp;b=&quot;2&quot;" rel="noopener noreferrer" target="_blank">&lt;b&gt;hi&lt;/b&gt;</a>"#,
+                r#"<img src="https://example.com/x.png" alt="&quot;&gt;&lt;script&gt;"></p>"#,
+            )
+        );
+
+        assert_eq!(
+            render_ops(json!([
+                { "insert": "click", "attributes": { "link": "javascript:alert(1)", "bold": true } },
+                { "insert": { "image": "javascript:alert(1)" } },
+                { "insert": "\n" },
+            ])),
+            "<p><strong>click</strong></p>"
+        );
+    }
+
+    #[test]
+    fn nests_lists() {
+        assert_eq!(
+            render_ops(json!([
+                { "insert": "a" },
+                { "insert": "\n", "attributes": { "list": "bullet" } },
+                { "insert": "b" },
+                { "insert": "\n", "attributes": { "list": "ordered", "indent": 1 } },
+                { "insert": "c" },
+                { "insert": "\n", "attributes": { "list": "ordered", "indent": 1 } },
+                { "insert": "d" },
+                { "insert": "\n", "attributes": { "list": "bullet" } },
+                { "insert": "e" },
+                { "insert": "\n", "attributes": { "list": "checked" } },
+                { "insert": "after\n" },
+            ])),
+            concat!(
+                "<ul><li>a<ol><li>b</li><li>c</li></ol></li><li>d</li>",
+                r#"<li data-checked="true">e</li></ul>"#,
+                "<p>after</p>",
+            )
+        );
+
+        // Skipped levels still produce valid nesting, and deep indents are capped
+        assert_eq!(
+            render_ops(json!([
+                { "insert": "deep" },
+                { "insert": "\n", "attributes": { "list": "bullet", "indent": 2 } },
+            ])),
+            "<ul><li><ul><li><ul><li>deep</li></ul></li></ul></li></ul>"
+        );
+        assert_eq!(
+            render_ops(json!([
+                { "insert": "deeper" },
+                { "insert": "\n", "attributes": { "list": "bullet", "indent": 100 } },
+            ]))
+            .matches("<ul>")
+            .count(),
+            MAX_INDENT + 1
+        );
+    }
+
+    #[test]
+    fn groups_code_blocks() {
+        assert_eq!(
+            render_ops(json!([
+                { "insert": "fn main() {" },
+                { "insert": "\n", "attributes": { "code-block": true } },
+                { "insert": "    println!(\"<hi>\");" },
+                { "insert": "\n", "attributes": { "code-block": true } },
+                { "insert": "}" },
+                { "insert": "\n", "attributes": { "code-block": true } },
+                { "insert": "text\n" },
+                { "insert": "again" },
+                { "insert": "\n", "attributes": { "code-block": true } },
+            ])),
+            concat!(
+                "<pre><code>fn main() {\n    println!(&quot;&lt;hi&gt;&quot;);\n}</code></pre>",
+                "<p>text</p>",
+                "<pre><code>again</code></pre>",
+            )
+        );
+    }
+}
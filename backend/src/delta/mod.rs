@@ -0,0 +1,107 @@
+//! Quill Delta helpers
+//!
+//! Post content is stored as the raw Delta produced by the editor (`{"ops":[...]}`).
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+pub mod diff;
+pub mod html;
+pub mod text;
+
+pub type Attributes = Map<String, Value>;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Delta {
+    #[serde(default)]
+    pub ops: Vec<Op>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Op {
+    /// Only inserts are expected in a stored document - retain/delete ops are ignored.
+    pub insert: Option<Insert>,
+    #[serde(default)]
+    pub attributes: Attributes,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Insert {
+    Text(String),
+    /// eg. `{"image": "https://..."}`
+    Embed(Attributes),
+}
+
+/// A single line of the document. Quill stores block formats (headers, lists, etc.) on the newline ending the line.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct Line {
+    pub segments: Vec<Segment>,
+    pub attributes: Attributes,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Segment {
+    pub insert: Insert,
+    pub attributes: Attributes,
+}
+
+impl Delta {
+    /// Invalid content is treated as an empty document.
+    pub fn from_value(value: &Value) -> Self {
+        Self::deserialize(value).unwrap_or_default()
+    }
+
+    pub fn lines(&self) -> Vec<Line> {
+        let mut lines = Vec::new();
+        let mut current = Line::default();
+
+        for op in &self.ops {
+            match &op.insert {
+                Some(Insert::Text(text)) => {
+                    let mut parts = text.split('\n').peekable();
+
+                    while let Some(part) = parts.next() {
+                        if !part.is_empty() {
+                            current.segments.push(Segment {
+                                insert: Insert::Text(part.to_string()),
+                                attributes: op.attributes.clone(),
+                            });
+                        }
+
+                        // Every part except the last was followed by a newline
+                        if parts.peek().is_some() {
+                            current.attributes = op.attributes.clone();
+                            lines.push(std::mem::take(&mut current));
+                        }
+                    }
+                }
+
+                Some(Insert::Embed(embed)) => current.segments.push(Segment {
+                    insert: Insert::Embed(embed.clone()),
+                    attributes: op.attributes.clone(),
+                }),
+
+                None => (),
+            }
+        }
+
+        if !current.segments.is_empty() {
+            lines.push(current);
+        }
+
+        lines
+    }
+}
+
+impl Line {
+    pub fn text(&self) -> String {
+        self.segments
+            .iter()
+            .filter_map(|seg| match &seg.insert {
+                Insert::Text(text) => Some(text.as_str()),
+                Insert::Embed(_) => None,
+            })
+            .collect()
+    }
+}
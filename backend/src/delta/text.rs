@@ -0,0 +1,57 @@
+use super::Delta;
+
+pub const EXCERPT_LENGTH: usize = 200;
+pub const WORDS_PER_MINUTE: usize = 220;
+
+/// Text content of every line, one line per row. Embeds are skipped.
+pub fn plain_text(delta: &Delta) -> String {
+    delta
+        .lines()
+        .iter()
+        .map(|line| line.text())
+        .collect::<Vec<_>>()
+        .join("\n")
+        // Search uses control characters to mark matches
+        .replace(|c: char| c.is_control() && !matches!(c, '\n' | '\t'), "")
+}
+
+/// First `max_chars` characters of the text, cut at the last word boundary.
+///
+/// Whitespace (including line breaks) is collapsed into single spaces.
+pub fn excerpt(text: &str, max_chars: usize) -> String {
+    let mut value = String::new();
+    let mut length = 0;
+
+    for word in text.split_whitespace() {
+        let needed = if value.is_empty() { 0 } else { 1 } + word.chars().count();
+
+        if length + needed > max_chars {
+            if value.is_empty() {
+                // A single word longer than the excerpt
+                value = word.chars().take(max_chars).collect();
+            }
+
+            value.push('…');
+
+            return value;
+        }
+
+        if !value.is_empty() {
+            value.push(' ');
+        }
+
+        value.push_str(word);
+        length += needed;
+    }
+
+    value
+}
+
+pub fn word_count(text: &str) -> usize {
+    text.split_whitespace().count()
+}
+
+/// Rounded up - anything with words in it takes at least a minute.
+pub fn reading_minutes(word_count: usize) -> usize {
+    word_count.div_ceil(WORDS_PER_MINUTE)
+}
@@ -1,5 +1,8 @@
 use webby_addon_common::WrappingResponse;
-use axum::response::{IntoResponse, Json, Response};
+use axum::{
+    http::header,
+    response::{IntoResponse, Json, Response},
+};
 use hyper::StatusCode;
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -25,6 +28,9 @@ pub enum Error {
     #[error("Sqlx Migration Error: {0}")]
     SqlxMigration(#[from] sqlx::migrate::MigrateError),
 
+    #[error("Time Format Error: {0}")]
+    TimeFormat(#[from] time::error::Format),
+
     #[error("UUID Error: {0}")]
     UUID(#[from] uuid::Error),
 
@@ -35,14 +41,67 @@ pub enum Error {
 
     #[error("Convert PathBuf to String Error")]
     ConvertPathBufToString,
+
+    /// The request is invalid, with a reason to show the user
+    #[error("{0}")]
+    BadRequest(String),
+
+    /// Clashes with something that already exists
+    #[error("{0}")]
+    Conflict(&'static str),
+
+    /// Doesn't exist, or belongs to another blog
+    #[error("{0}")]
+    NotFound(&'static str),
+
+    /// The signed in member isn't allowed to do this
+    #[error("Forbidden: {0}")]
+    Forbidden(&'static str),
+
+    /// Seconds until the client may retry
+    #[error("Too many requests, try again in {0} seconds")]
+    RateLimited(u64),
 }
 
 impl IntoResponse for Error {
     fn into_response(self) -> Response {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(WrappingResponse::<()>::error(self.to_string())),
-        )
-            .into_response()
+        match self {
+            Self::RateLimited(retry_after) => (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, retry_after.to_string())],
+                Json(WrappingResponse::<()>::error(self.to_string())),
+            )
+                .into_response(),
+
+            Self::BadRequest(_) => (
+                StatusCode::BAD_REQUEST,
+                Json(WrappingResponse::<()>::error(self.to_string())),
+            )
+                .into_response(),
+
+            Self::Conflict(_) => (
+                StatusCode::CONFLICT,
+                Json(WrappingResponse::<()>::error(self.to_string())),
+            )
+                .into_response(),
+
+            Self::NotFound(_) => (
+                StatusCode::NOT_FOUND,
+                Json(WrappingResponse::<()>::error(self.to_string())),
+            )
+                .into_response(),
+
+            Self::Forbidden(_) => (
+                StatusCode::FORBIDDEN,
+                Json(WrappingResponse::<()>::error(self.to_string())),
+            )
+                .into_response(),
+
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(WrappingResponse::<()>::error(self.to_string())),
+            )
+                .into_response(),
+        }
     }
 }
@@ -6,7 +6,10 @@ use tracing_subscriber::util::SubscriberInitExt;
 
 mod api;
 mod database;
+mod delta;
 mod error;
+mod scheduler;
+mod spam;
 mod upload;
 
 pub use database::id::*;
@@ -29,5 +32,7 @@ async fn main() -> Result<()> {
 
     let (_is_new, pool) = database::init().await?;
 
+    scheduler::spawn(pool.clone());
+
     Ok(api::serve(pool).await?)
 }
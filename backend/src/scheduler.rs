@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use sqlx::SqlitePool;
+use time::OffsetDateTime;
+
+use crate::models::PostModel;
+
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(60);
+
+const DEFAULT_TRASH_RETENTION_DAYS: i64 = 30;
+
+lazy_static! {
+    /// How long posts stay in the trash before they're purged. Set with `POST_TRASH_RETENTION_DAYS`.
+    pub static ref TRASH_RETENTION: time::Duration = time::Duration::days(
+        std::env::var("POST_TRASH_RETENTION_DAYS")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .filter(|v: &i64| *v >= 0)
+            .unwrap_or(DEFAULT_TRASH_RETENTION_DAYS)
+    );
+}
+
+/// Background tasks which run for the lifetime of the process.
+pub fn spawn(pool: SqlitePool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SCHEDULER_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(error) = publish_scheduled_posts(&pool).await {
+                error!("Scheduler Error: {error}");
+            }
+
+            if let Err(error) = purge_trashed_posts(&pool).await {
+                error!("Scheduler Error: {error}");
+            }
+        }
+    });
+}
+
+async fn publish_scheduled_posts(pool: &SqlitePool) -> eyre::Result<()> {
+    let mut acq = pool.acquire().await?;
+
+    let published = PostModel::publish_scheduled(&mut acq).await?;
+
+    if published != 0 {
+        debug!("Published {published} scheduled post(s)");
+    }
+
+    Ok(())
+}
+
+async fn purge_trashed_posts(pool: &SqlitePool) -> eyre::Result<()> {
+    let mut acq = pool.acquire().await?;
+
+    let purged =
+        PostModel::purge_deleted_before(OffsetDateTime::now_utc() - *TRASH_RETENTION, &mut acq)
+            .await?;
+
+    if purged != 0 {
+        debug!("Purged {purged} post(s) from the trash");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,220 @@
+use async_trait::async_trait;
+use sqlx::SqliteConnection;
+use time::Duration;
+
+use super::{token, tokenize, SpamCheck, SpamInput, SpamSignal};
+use crate::{
+    models::{SpamBlocklistModel, SpamCorpusModel, SpamTokenModel},
+    Result,
+};
+
+/// Flags comments which filled in the hidden honeypot field.
+pub struct HoneypotCheck;
+
+#[async_trait]
+impl SpamCheck for HoneypotCheck {
+    async fn check(
+        &self,
+        input: &SpamInput<'_>,
+        _db: &mut SqliteConnection,
+    ) -> Result<Option<SpamSignal>> {
+        Ok(input
+            .honeypot
+            .filter(|v| !v.trim().is_empty())
+            .map(|_| SpamSignal::new("honeypot", 1.0, "Hidden field was filled in")))
+    }
+}
+
+/// Submitted faster than a person could type
+pub const MIN_SUBMIT_TIME: Duration = Duration::seconds(3);
+/// Form was loaded too long ago
+pub const MAX_SUBMIT_TIME: Duration = Duration::days(1);
+
+/// Checks the form token for how long it took to submit the comment.
+pub struct SubmitTimeCheck;
+
+#[async_trait]
+impl SpamCheck for SubmitTimeCheck {
+    async fn check(
+        &self,
+        input: &SpamInput<'_>,
+        _db: &mut SqliteConnection,
+    ) -> Result<Option<SpamSignal>> {
+        const NAME: &str = "submit_time";
+
+        let Some(value) = input.token else {
+            return Ok(Some(SpamSignal::new(NAME, 0.5, "No form token")));
+        };
+
+        let Some(issued) = token::verify(value, input.post.id) else {
+            // Also the case for forms loaded before the secret changed, so not spam on its own
+            return Ok(Some(SpamSignal::new(NAME, 0.5, "Invalid form token")));
+        };
+
+        let elapsed = input.submitted_at - issued;
+
+        if elapsed < MIN_SUBMIT_TIME {
+            Ok(Some(SpamSignal::new(
+                NAME,
+                1.0,
+                format!("Submitted {:.1}s after loading", elapsed.as_seconds_f64()),
+            )))
+        } else if elapsed > MAX_SUBMIT_TIME {
+            Ok(Some(SpamSignal::new(NAME, 0.5, "Form token expired")))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Most links a comment can have before being flagged
+pub const MAX_LINKS: usize = 2;
+
+/// Flags comments with too many links, or a link in the name.
+pub struct LinkCheck;
+
+#[async_trait]
+impl SpamCheck for LinkCheck {
+    async fn check(
+        &self,
+        input: &SpamInput<'_>,
+        _db: &mut SqliteConnection,
+    ) -> Result<Option<SpamSignal>> {
+        const NAME: &str = "links";
+
+        if count_links(input.author_name) != 0 {
+            return Ok(Some(SpamSignal::new(NAME, 1.0, "Link in the name")));
+        }
+
+        let count = count_links(input.comment);
+
+        if count > MAX_LINKS {
+            Ok(Some(SpamSignal::new(
+                NAME,
+                1.0,
+                format!("{count} links, at most {MAX_LINKS} are allowed"),
+            )))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+fn count_links(value: &str) -> usize {
+    let value = value.to_lowercase();
+
+    value.matches("http://").count()
+        + value.matches("https://").count()
+        + value
+            .split_whitespace()
+            .filter(|v| v.starts_with("www."))
+            .count()
+        + value.matches("[url").count()
+        + value.matches("<a ").count()
+}
+
+/// Flags comments containing a word from the blog's blocklist.
+pub struct BlocklistCheck;
+
+#[async_trait]
+impl SpamCheck for BlocklistCheck {
+    async fn check(
+        &self,
+        input: &SpamInput<'_>,
+        db: &mut SqliteConnection,
+    ) -> Result<Option<SpamSignal>> {
+        let blocklist = SpamBlocklistModel::find_by_blog_id(input.blog_id, db).await?;
+
+        if blocklist.is_empty() {
+            return Ok(None);
+        }
+
+        let haystack = format!(
+            "{}\n{}\n{}",
+            input.author_name,
+            input.email.unwrap_or_default(),
+            input.comment
+        )
+        .to_lowercase();
+
+        Ok(blocklist
+            .into_iter()
+            .find(|v| haystack.contains(&v.word.to_lowercase()))
+            .map(|v| SpamSignal::new("blocklist", 1.0, format!("Contains \"{}\"", v.word))))
+    }
+}
+
+/// Both spam and ham need this many trained comments before the classifier is used
+pub const MIN_TRAINED_COMMENTS: i64 = 5;
+/// Number of tokens furthest from neutral which are combined
+const INTERESTING_TOKENS: usize = 15;
+
+/// Naive Bayes classifier trained from moderator decisions.
+///
+/// Token probabilities use Robinson's smoothing so rarely seen tokens stay close to neutral.
+pub struct BayesCheck;
+
+#[async_trait]
+impl SpamCheck for BayesCheck {
+    async fn check(
+        &self,
+        input: &SpamInput<'_>,
+        db: &mut SqliteConnection,
+    ) -> Result<Option<SpamSignal>> {
+        const NAME: &str = "bayes";
+
+        let corpus = SpamCorpusModel::find_one_by_blog_id(input.blog_id, &mut *db).await?;
+
+        if corpus.spam_count < MIN_TRAINED_COMMENTS || corpus.ham_count < MIN_TRAINED_COMMENTS {
+            return Ok(None);
+        }
+
+        let tokens = tokenize(input.author_name, input.comment);
+        let counts = SpamTokenModel::find_by_tokens(input.blog_id, &tokens, db).await?;
+
+        let mut probabilities = counts
+            .iter()
+            .map(|v| {
+                let spam = v.spam_count as f64 / corpus.spam_count as f64;
+                let ham = v.ham_count as f64 / corpus.ham_count as f64;
+                let seen = (v.spam_count + v.ham_count) as f64;
+
+                let probability = if spam + ham == 0.0 {
+                    0.5
+                } else {
+                    spam / (spam + ham)
+                };
+
+                (0.5 + seen * probability) / (1.0 + seen)
+            })
+            .collect::<Vec<_>>();
+
+        probabilities.sort_by(|a, b| (b - 0.5).abs().total_cmp(&(a - 0.5).abs()));
+        probabilities.truncate(INTERESTING_TOKENS);
+
+        if probabilities.is_empty() {
+            return Ok(None);
+        }
+
+        // Combined in log space to avoid underflow
+        let (spam, ham) = probabilities.iter().fold((0.0, 0.0), |(spam, ham), p| {
+            (spam + f64::ln(*p), ham + f64::ln(1.0 - p))
+        });
+
+        let probability = 1.0 / (1.0 + (ham - spam).exp());
+
+        let score = if probability >= 0.9 {
+            1.0
+        } else if probability >= 0.75 {
+            0.5
+        } else {
+            return Ok(None);
+        };
+
+        Ok(Some(SpamSignal::new(
+            NAME,
+            score,
+            format!("{:.0}% likely spam", probability * 100.0),
+        )))
+    }
+}
@@ -0,0 +1,189 @@
+//! Spam checks run on every submitted comment
+
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sqlx::SqliteConnection;
+use time::OffsetDateTime;
+
+use crate::{
+    models::{CommentModel, PostModel, SpamTokenModel},
+    BlogId, Result,
+};
+
+mod checks;
+pub mod token;
+
+pub use checks::*;
+
+/// Total score at which a comment is flagged as spam.
+pub const SPAM_THRESHOLD: f64 = 1.0;
+
+const MIN_TOKEN_LENGTH: usize = 3;
+const MAX_TOKEN_LENGTH: usize = 24;
+const MAX_TOKENS: usize = 200;
+
+/// The submitted comment, as seen by the checks.
+pub struct SpamInput<'a> {
+    pub blog_id: BlogId,
+    pub post: &'a PostModel,
+
+    pub author_name: &'a str,
+    pub email: Option<&'a str>,
+    pub comment: &'a str,
+
+    /// Honeypot field - hidden from readers, so only bots fill it in
+    pub honeypot: Option<&'a str>,
+    /// Form token handed out when the comment form was loaded
+    pub token: Option<&'a str>,
+
+    pub submitted_at: OffsetDateTime,
+}
+
+/// Why a check considers the comment spam. Scores of every signal are added up.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpamSignal {
+    pub check: String,
+    pub score: f64,
+    pub reason: String,
+}
+
+impl SpamSignal {
+    pub fn new(check: &str, score: f64, reason: impl Into<String>) -> Self {
+        Self {
+            check: check.to_string(),
+            score,
+            reason: reason.into(),
+        }
+    }
+}
+
+pub struct SpamVerdict {
+    pub score: f64,
+    pub signals: Vec<SpamSignal>,
+}
+
+impl SpamVerdict {
+    pub fn is_spam(&self) -> bool {
+        self.score >= SPAM_THRESHOLD
+    }
+}
+
+#[async_trait]
+pub trait SpamCheck: Send + Sync {
+    async fn check(
+        &self,
+        input: &SpamInput<'_>,
+        db: &mut SqliteConnection,
+    ) -> Result<Option<SpamSignal>>;
+}
+
+/// Checks run on every comment, cheapest first.
+pub fn default_checks() -> Vec<Box<dyn SpamCheck>> {
+    vec![
+        Box::new(HoneypotCheck),
+        Box::new(SubmitTimeCheck),
+        Box::new(LinkCheck),
+        Box::new(BlocklistCheck),
+        Box::new(BayesCheck),
+    ]
+}
+
+/// Checks which only look at what was written, for comments edited after submitting.
+pub fn content_checks() -> Vec<Box<dyn SpamCheck>> {
+    vec![
+        Box::new(LinkCheck),
+        Box::new(BlocklistCheck),
+        Box::new(BayesCheck),
+    ]
+}
+
+pub async fn run_checks(
+    checks: &[Box<dyn SpamCheck>],
+    input: &SpamInput<'_>,
+    db: &mut SqliteConnection,
+) -> Result<SpamVerdict> {
+    let mut signals = Vec::new();
+
+    for check in checks {
+        if let Some(signal) = check.check(input, &mut *db).await? {
+            signals.push(signal);
+        }
+    }
+
+    Ok(SpamVerdict {
+        score: signals.iter().map(|v| v.score).sum(),
+        signals,
+    })
+}
+
+/// Teaches the classifier the moderator's decision, undoing any earlier opposite training of the comment.
+pub async fn train(comment: &CommentModel, is_spam: bool, db: &mut SqliteConnection) -> Result<()> {
+    if comment.trained_spam == Some(is_spam) {
+        return Ok(());
+    }
+
+    let tokens = tokenize(&comment.author_name, &comment.comment);
+
+    if let Some(previous) = comment.trained_spam {
+        SpamTokenModel::train(comment.blog_id, &tokens, previous, -1, &mut *db).await?;
+    }
+
+    SpamTokenModel::train(comment.blog_id, &tokens, is_spam, 1, &mut *db).await?;
+
+    CommentModel::update_trained_spam(comment.blog_id, comment.id, Some(is_spam), db).await?;
+
+    Ok(())
+}
+
+/// Forgets what the classifier learned from the comment, e.g. before its text changes.
+pub async fn untrain(comment: &mut CommentModel, db: &mut SqliteConnection) -> Result<()> {
+    let Some(previous) = comment.trained_spam else {
+        return Ok(());
+    };
+
+    let tokens = tokenize(&comment.author_name, &comment.comment);
+
+    SpamTokenModel::train(comment.blog_id, &tokens, previous, -1, &mut *db).await?;
+
+    CommentModel::update_trained_spam(comment.blog_id, comment.id, None, db).await?;
+
+    comment.trained_spam = None;
+
+    Ok(())
+}
+
+/// Unique lowercase words of the comment used by the classifier.
+pub fn tokenize(author_name: &str, comment: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut tokens = Vec::new();
+
+    let words = comment
+        .split(|c: char| !c.is_alphanumeric() && c != '$' && c != '\'')
+        .map(|v| v.trim_matches('\'').to_lowercase())
+        // Names are kept apart, "cheap" in a name is a different signal than in the text
+        .chain(
+            author_name
+                .split(|c: char| !c.is_alphanumeric())
+                .map(|v| format!("name:{}", v.to_lowercase())),
+        );
+
+    for word in words {
+        let length = word.trim_start_matches("name:").chars().count();
+
+        if !(MIN_TOKEN_LENGTH..=MAX_TOKEN_LENGTH).contains(&length) {
+            continue;
+        }
+
+        if seen.insert(word.clone()) {
+            tokens.push(word);
+
+            if tokens.len() == MAX_TOKENS {
+                break;
+            }
+        }
+    }
+
+    tokens
+}
@@ -0,0 +1,90 @@
+//! Signed form tokens recording when the comment form was loaded.
+//!
+//! Format: `{issued unix timestamp}.{hex HMAC-SHA256 of "{post id}.{issued}"}`
+
+use hmac::{Hmac, Mac};
+use lazy_static::lazy_static;
+use sha2::Sha256;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::PostId;
+
+lazy_static! {
+    /// Tokens issued before a restart stay valid only when the secret is configured.
+    static ref SECRET: Vec<u8> = std::env::var("COMMENT_TOKEN_SECRET")
+        .map(String::into_bytes)
+        .unwrap_or_else(|_| {
+            warn!("COMMENT_TOKEN_SECRET is not set, open comment forms break on restart");
+
+            [Uuid::new_v4().into_bytes(), Uuid::new_v4().into_bytes()].concat()
+        });
+}
+
+pub fn issue(post_id: PostId, now: OffsetDateTime) -> String {
+    let issued = now.unix_timestamp();
+    let signature = mac(post_id, issued).finalize().into_bytes();
+
+    format!("{issued}.{signature:x}")
+}
+
+/// Returns when the token was issued if it's valid for the post.
+pub fn verify(token: &str, post_id: PostId) -> Option<OffsetDateTime> {
+    let (issued, signature) = token.split_once('.')?;
+    let issued = issued.parse::<i64>().ok()?;
+    let signature = decode_hex(signature)?;
+
+    // Compares in constant time
+    mac(post_id, issued).verify_slice(&signature).ok()?;
+
+    OffsetDateTime::from_unix_timestamp(issued).ok()
+}
+
+fn mac(post_id: PostId, issued: i64) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(&SECRET).expect("HMAC accepts keys of any length");
+
+    mac.update(format!("{post_id}.{issued}").as_bytes());
+
+    mac
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    // A dangling last digit fails to slice
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(value.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verifies_own_tokens() {
+        let now = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let token = issue(PostId::from(1), now);
+
+        assert_eq!(verify(&token, PostId::from(1)), Some(now));
+        assert_eq!(verify(&token, PostId::from(2)), None);
+
+        let (issued, signature) = token.split_once('.').unwrap();
+
+        assert_eq!(
+            verify(
+                &format!("{}.{signature}", issued.parse::<i64>().unwrap() - 60),
+                PostId::from(1)
+            ),
+            None
+        );
+        assert_eq!(
+            verify(&format!("{issued}.{}", &signature[2..]), PostId::from(1)),
+            None
+        );
+        assert_eq!(
+            verify(&format!("{issued}.zz{}", &signature[2..]), PostId::from(1)),
+            None
+        );
+        assert_eq!(verify(issued, PostId::from(1)), None);
+    }
+}